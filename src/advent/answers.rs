@@ -3,50 +3,57 @@ use crate::advent::q12_hillwalking;
 use super::{
     q01_calories,
     q02_rock_paper_scissors::{self as rps, ParseMode},
-    q03_rucksack, q04_cleaning,
+    q03_rucksack::{self, groups::Grouping},
+    q04_cleaning,
     q05_supply_crates::{self, Crane},
     q06_datastream, q07_directories, q08_trees, q09_rope, q10_cathode, q11_monkeys,
 };
 
 pub fn one() {
-    let elf_calories =
-        q01_calories::highest_total_calories_from_file("resources/01_elf_calories.txt");
+    let inventory = q01_calories::ElfInventory::from_file("resources/01_elf_calories.txt")
+        .expect("Could not read elf inventory");
+    let top3 = inventory.top_n(3);
 
-    let length = elf_calories.len();
+    match top3.first() {
+        Some(elf) => println!("Part 1: Highest total calories = {}", elf.total()),
+        None => println!("Part 1: There are no elves"),
+    }
 
-    println!(
-        "Part 1: Highest total calories = {}",
-        elf_calories[length - 1]
-    );
-
-    let top3: &[u32] = &elf_calories[length - 3..length];
     println!(
         "Part 2: Sum of highest 3 calories = {}",
-        top3.iter().sum::<u32>()
+        top3.iter().map(|elf| elf.total()).sum::<u64>()
     );
 }
 
 pub fn two() {
     let filename = "resources/02_rock_paper_scissors.txt";
-    let rps_score_total = rps::calculate_score_for_file(filename, ParseMode::Choice);
+    let rules =
+        rps::rules::Rules::from_file("resources/02_rules.txt").expect("Could not read rules");
+    let rps_score_total =
+        rps::calculate_score_for_file_with_rules(filename, ParseMode::Choice, &rules);
     println!("Part 1: Total score for Rock Paper Scissors = {rps_score_total}");
 
-    let rps_score_total = rps::calculate_score_for_file(filename, ParseMode::Result);
+    let rps_score_total =
+        rps::calculate_score_for_file_with_rules(filename, ParseMode::Result, &rules);
     println!("Part 2: Total score for Rock Paper Scissors = {rps_score_total}");
 }
 
 pub fn three() {
-    let rucksacks = q03_rucksack::get_rucksacks_from_file("resources/03_rucksack.txt");
+    let rucksacks = q03_rucksack::get_rucksacks_from_file("resources/03_rucksack.txt")
+        .expect("Could not read rucksacks");
 
     let priority_sum = q03_rucksack::get_sum_of_priorities_for_common_items(&rucksacks);
     println!("Part 1: Sum of priorities for items = {priority_sum}");
 
-    let group_sum = q03_rucksack::get_sum_of_priorities_for_group(&rucksacks);
+    let group_sum =
+        q03_rucksack::get_sum_of_priorities_for_group(&rucksacks, Grouping::Consecutive(3))
+            .expect("Could not group rucksacks");
     println!("Part 2: Sum of priorities for group items = {group_sum}");
 }
 
 pub fn four() {
-    let pairs = q04_cleaning::get_cleaning_pairs("resources/04_cleaning_pairs.txt");
+    let pairs = q04_cleaning::get_cleaning_pairs("resources/04_cleaning_pairs.txt")
+        .expect("Could not read cleaning pairs");
 
     let count = q04_cleaning::get_count_of_fully_overlapping_pairs(&pairs);
     println!("Part 1: Number of fully overlapping cleaning pairs = {count}");
@@ -128,6 +135,7 @@ pub fn twelve() {
     let result = q12_hillwalking::get_fewest_steps_from_start("resources/12_hillwalking.txt");
     println!("Part 1: Fewest steps from start to peak is {result}");
 
-    let result = q12_hillwalking::get_fewest_steps_from_low_elevation("resources/12_hillwalking.txt");
+    let result =
+        q12_hillwalking::get_fewest_steps_from_low_elevation("resources/12_hillwalking.txt");
     println!("Part 2: Fewest steps from low elevation is {result}");
 }
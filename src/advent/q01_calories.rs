@@ -1,25 +1,253 @@
-use crate::common::io;
+use crate::common::io::{Input, LineReader};
+use crate::common::str::{Line, ParseError, Section};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
 
-pub fn highest_total_calories_from_file(filename: &str) -> Vec<u32> {
-    let lines = io::read_file_as_vector(filename).expect("Could not read file");
+/// Every elf's items, in the order the elves appear in the input.
+#[derive(Debug, Default)]
+pub struct ElfInventory {
+    elves: Vec<Elf>,
+}
+
+/// Keeps the `k` highest calorie totals while elves are read one line at a time, so inputs with millions
+/// of elves never have to be held in memory. Lines that can't be used are set aside rather than stopping
+/// the count.
+#[derive(Debug)]
+pub struct TopCalories {
+    best: Best<(u64, Reverse<usize>)>,
+    current: Option<u64>,
+    elves: usize,
+    rejected: Vec<ParseError>,
+}
+
+/// The result of a `TopCalories` count.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TopTotals {
+    /// (elf index, total) for the highest totals, most first. Equal totals are in input order.
+    pub totals: Vec<(usize, u64)>,
+    pub elves: usize,
+    /// Lines that weren't a number of calories, or that would have overflowed their elf's total.
+    pub rejected: Vec<ParseError>,
+}
+
+// the `k` greatest items pushed so far
+#[derive(Debug)]
+struct Best<T> {
+    k: usize,
+    // min-heap of the best k so far, so the worst of them is the one pushed out
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Elf {
+    /// Position of the elf in the input, starting at 0.
+    pub index: usize,
+    /// Calories of each item carried.
+    pub items: Vec<u32>,
+}
+
+#[allow(dead_code)]
+impl ElfInventory {
+    pub fn from_file(filename: &str) -> Result<Self, std::io::Error> {
+        let input = Input::read(filename)?;
+        Ok(Self::from_input(&input).map_err(|e| e.in_file(input.name()))?)
+    }
+
+    /// Each elf's items are listed one per line, with a blank line between elves.
+    pub fn from_input(input: &Input) -> Result<Self, ParseError> {
+        let elves = input
+            .sections()
+            .iter()
+            .enumerate()
+            .map(|(index, section)| Elf::from_section(index, section))
+            .collect::<Result<_, _>>()?;
+
+        Ok(ElfInventory { elves })
+    }
 
-    let mut elf_calories = Vec::<u32>::new();
-    let mut cal_add: u32 = 0;
+    pub fn elves(&self) -> &[Elf] {
+        &self.elves
+    }
+
+    pub fn len(&self) -> usize {
+        self.elves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elves.is_empty()
+    }
+
+    /// The `n` elves carrying the most calories, most first. Elves with equal totals are in input order.
+    pub fn top_n(&self, n: usize) -> Vec<&Elf> {
+        let mut best = Best::new(n);
+        for elf in self.elves.iter() {
+            best.push((elf.rank_key(), elf.index));
+        }
 
-    for l in lines {
-        if l.is_empty() {
-            elf_calories.push(cal_add);
-            cal_add = 0;
-        } else {
-            let cal: u32 = l.trim().parse().expect("Cannot parse line as number");
-            cal_add += cal;
+        best.into_sorted_vec()
+            .into_iter()
+            .map(|(_, index)| &self.elves[index])
+            .collect()
+    }
+
+    /// Position of the elf at `index` when elves are ordered as in `top_n`, starting at 1.
+    pub fn rank(&self, index: usize) -> Option<usize> {
+        let key = self.elves.get(index)?.rank_key();
+        Some(1 + self.elves.iter().filter(|e| e.rank_key() > key).count())
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.elves.is_empty() {
+            return None;
         }
+
+        let sum: u64 = self.elves.iter().map(|e| e.total()).sum();
+        Some(sum as f64 / self.elves.len() as f64)
     }
-    elf_calories.push(cal_add);
 
-    elf_calories.sort();
+    /// Middle total. With an even number of elves, the mean of the two middle totals.
+    pub fn median(&self) -> Option<f64> {
+        let mut totals: Vec<u64> = self.elves.iter().map(|e| e.total()).collect();
+        totals.sort_unstable();
 
-    elf_calories
+        let middle = totals.len() / 2;
+        match totals.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(totals[middle] as f64),
+            _ => Some((totals[middle - 1] + totals[middle]) as f64 / 2.0),
+        }
+    }
+
+    /// Number of items carried by each elf, in input order.
+    pub fn item_counts(&self) -> Vec<usize> {
+        self.elves.iter().map(|e| e.items.len()).collect()
+    }
+}
+
+#[allow(dead_code)]
+impl TopCalories {
+    pub fn new(k: usize) -> Self {
+        TopCalories {
+            best: Best::new(k),
+            current: None,
+            elves: 0,
+            rejected: Vec::new(),
+        }
+    }
+
+    /// Count every line of `reader`, keeping the `k` highest totals.
+    pub fn from_reader<R: BufRead>(
+        reader: &mut LineReader<R>,
+        k: usize,
+    ) -> Result<TopTotals, std::io::Error> {
+        let mut top = TopCalories::new(k);
+
+        while let Some(line) = reader.next_line() {
+            top.push(&line?);
+        }
+
+        let mut totals = top.finish();
+        let name = reader.name().to_string();
+        totals.rejected = totals
+            .rejected
+            .into_iter()
+            .map(|e| e.in_file(&name))
+            .collect();
+
+        Ok(totals)
+    }
+
+    /// Add the next line. A blank line ends the current elf.
+    pub fn push(&mut self, line: &Line) {
+        if line.text.trim().is_empty() {
+            self.end_elf();
+            return;
+        }
+
+        let calories = match line.parse::<u64>() {
+            Ok(calories) => calories,
+            Err(e) => return self.rejected.push(e),
+        };
+
+        // an elf only starts with a line that parses
+        let total = self.current.get_or_insert(0);
+        match total.checked_add(calories) {
+            Some(sum) => *total = sum,
+            None => self
+                .rejected
+                .push(line.error("total calories for this elf overflow")),
+        }
+    }
+
+    pub fn finish(mut self) -> TopTotals {
+        self.end_elf();
+
+        TopTotals {
+            totals: self
+                .best
+                .into_sorted_vec()
+                .into_iter()
+                .map(|(total, Reverse(index))| (index, total))
+                .collect(),
+            elves: self.elves,
+            rejected: self.rejected,
+        }
+    }
+
+    fn end_elf(&mut self) {
+        let Some(total) = self.current.take() else {
+            return;
+        };
+
+        self.best.push((total, Reverse(self.elves)));
+        self.elves += 1;
+    }
+}
+
+impl<T: Ord> Best<T> {
+    fn new(k: usize) -> Self {
+        Best {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    fn push(&mut self, item: T) {
+        self.heap.push(Reverse(item));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    // the kept items, greatest first
+    fn into_sorted_vec(self) -> Vec<T> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(item)| item)
+            .collect()
+    }
+}
+
+impl Elf {
+    fn from_section(index: usize, section: &Section) -> Result<Self, ParseError> {
+        let items = section
+            .numbered()
+            .map(|l| l.parse())
+            .collect::<Result<_, _>>()?;
+
+        Ok(Elf { index, items })
+    }
+
+    pub fn total(&self) -> u64 {
+        self.items.iter().map(|c| *c as u64).sum()
+    }
+
+    // higher is better: more calories first, then earlier in the input
+    fn rank_key(&self) -> (u64, Reverse<usize>) {
+        (self.total(), Reverse(self.index))
+    }
 }
 
 //
@@ -29,12 +257,126 @@ pub fn highest_total_calories_from_file(filename: &str) -> Vec<u32> {
 mod tests {
     use super::*;
 
+    fn parse(text: &str) -> ElfInventory {
+        ElfInventory::from_input(&Input::new("test", text)).unwrap()
+    }
+
     #[test]
     fn test_calories_file_read_and_sum() {
-        let calories = highest_total_calories_from_file("resources/test/01_calories.txt");
+        let inventory = ElfInventory::from_file("resources/test/01_calories.txt").unwrap();
+
+        assert_eq!(5, inventory.len());
+        let totals: Vec<u64> = inventory.elves().iter().map(|e| e.total()).collect();
+        assert_eq!(vec![6000, 4000, 11000, 24000, 10000], totals);
+        assert_eq!(vec![3, 1, 2, 3, 1], inventory.item_counts());
+    }
+
+    #[test]
+    fn test_top_n() {
+        let inventory = ElfInventory::from_file("resources/test/01_calories.txt").unwrap();
+
+        let top: Vec<(usize, u64)> = inventory
+            .top_n(3)
+            .iter()
+            .map(|e| (e.index, e.total()))
+            .collect();
+        assert_eq!(vec![(3, 24000), (2, 11000), (4, 10000)], top);
+
+        assert_eq!(5, inventory.top_n(10).len());
+        assert!(inventory.top_n(0).is_empty());
+    }
+
+    #[test]
+    fn test_rank_and_ties() {
+        let inventory = parse("5\n\n3\n4\n\n7\n\n1");
+
+        let top: Vec<usize> = inventory.top_n(2).iter().map(|e| e.index).collect();
+        assert_eq!(vec![1, 2], top);
+
+        assert_eq!(Some(1), inventory.rank(1));
+        assert_eq!(Some(2), inventory.rank(2));
+        assert_eq!(Some(3), inventory.rank(0));
+        assert_eq!(Some(4), inventory.rank(3));
+        assert_eq!(None, inventory.rank(4));
+    }
+
+    #[test]
+    fn test_statistics() {
+        let inventory = parse("5\n\n3\n4\n\n7\n\n1");
+        assert_eq!(Some(5.0), inventory.mean());
+        assert_eq!(Some(6.0), inventory.median());
+
+        let inventory = parse("5\n\n3\n4\n\n1");
+        assert_eq!(Some(5.0), inventory.median());
+    }
+
+    #[test]
+    fn test_empty_and_blank_lines() {
+        let empty = parse("");
+        assert!(empty.is_empty());
+        assert!(empty.top_n(3).is_empty());
+        assert_eq!(None, empty.mean());
+        assert_eq!(None, empty.median());
+
+        let trailing = parse("\n1\n2\n\n\n3\n\n\n");
+        assert_eq!(vec![2, 1], trailing.item_counts());
+        assert_eq!(1, trailing.elves()[1].index);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = Input::new("calories.txt", "1\n\nten\n");
+        let error = ElfInventory::from_input(&input).unwrap_err();
+        assert_eq!(3, error.line);
+    }
+
+    #[test]
+    fn test_top_calories_matches_inventory() {
+        let filename = "resources/test/01_calories.txt";
+        let mut reader = LineReader::open(filename).unwrap();
+        let top = TopCalories::from_reader(&mut reader, 3).unwrap();
+
+        let inventory = ElfInventory::from_file(filename).unwrap();
+        let expected: Vec<(usize, u64)> = inventory
+            .top_n(3)
+            .iter()
+            .map(|e| (e.index, e.total()))
+            .collect();
+
+        assert_eq!(expected, top.totals);
+        assert_eq!(5, top.elves);
+        assert!(top.rejected.is_empty());
+    }
+
+    #[test]
+    fn test_top_calories_rejects_lines() {
+        let text = "5\n\n18446744073709551615\n1\nten\n\n\n7\n-2\n";
+        let mut reader = LineReader::new("stress.txt", text.as_bytes());
+        let top = TopCalories::from_reader(&mut reader, 2).unwrap();
+
+        assert_eq!(vec![(1, u64::MAX), (2, 7)], top.totals);
+        assert_eq!(3, top.elves);
+
+        let rejected: Vec<usize> = top.rejected.iter().map(|e| e.line).collect();
+        assert_eq!(vec![4, 5, 9], rejected);
+        assert!(top.rejected[0].message.contains("overflow"));
+        assert_eq!(Some("stress.txt".to_string()), top.rejected[1].file);
+    }
+
+    #[test]
+    fn test_top_calories_skips_blocks_of_rejected_lines() {
+        let text = "3
+
+ten
+eleven
+
+4
+";
+        let mut reader = LineReader::new("rejected.txt", text.as_bytes());
+        let top = TopCalories::from_reader(&mut reader, 3).unwrap();
 
-        assert_eq!(5, calories.len());
-        let expected_values: Vec<u32> = vec![4000, 6000, 10000, 11000, 24000];
-        assert_eq!(expected_values, calories);
+        assert_eq!(vec![(1, 4), (0, 3)], top.totals);
+        assert_eq!(2, top.elves);
+        assert_eq!(2, top.rejected.len());
     }
 }
@@ -1,38 +1,47 @@
-use crate::common::io;
+use crate::common::io::Input;
+use crate::common::str::{Line, ParseError};
 
-pub fn calculate_score_for_file(filename: &str, mode: ParseMode) -> u32 {
-    let lines = io::read_file_as_vector(filename).expect("Could not read file");
+pub mod analysis;
+pub mod rules;
 
-    let mut total_score: u32 = 0;
+use rules::{Outcome, Rules, Shape};
 
-    for l in lines {
-        total_score += Round::from_line(&l, &mode).score() as u32;
-    }
+#[allow(dead_code)]
+pub fn calculate_score_for_file(filename: &str, mode: ParseMode) -> u32 {
+    calculate_score_for_file_with_rules(filename, mode, &Rules::rock_paper_scissors())
+}
 
-    total_score
+pub fn calculate_score_for_file_with_rules(filename: &str, mode: ParseMode, rules: &Rules) -> u32 {
+    Guide::from_file(filename, mode, rules, Validation::Strict)
+        .expect("Could not read strategy guide")
+        .total_score(rules)
 }
 
 /// How we should parse/interpret the second column of the text file.
 ///     Choice: column is what you shape you should choose.
 ///     Result: column is the result you want to occur.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParseMode {
     Choice,
     Result,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Shape {
-    Rock,
-    Paper,
-    Scissors,
+/// How lines of the strategy guide that can't be read are handled.
+///     Strict: the first bad line is an error.
+///     Lenient: bad lines are skipped, and kept with the guide so they can be reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validation {
+    Strict,
+    Lenient,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum Result {
-    Win,
-    Loss,
-    Draw,
+/// The rounds of a strategy guide. Every line must be the opponent's letter, a single space and my letter,
+/// using the letters in the rules.
+#[derive(Debug)]
+pub struct Guide {
+    // each round with the letter in my column
+    rounds: Vec<(Round, char)>,
+    skipped: Vec<ParseError>,
 }
 
 #[derive(Debug)]
@@ -41,99 +50,136 @@ struct Round {
     mine: Shape,
 }
 
-impl Shape {
-    fn value(&self) -> u8 {
-        match *self {
-            Shape::Rock => 1,
-            Shape::Paper => 2,
-            Shape::Scissors => 3,
-        }
+#[allow(dead_code)]
+impl Guide {
+    pub fn from_file(
+        filename: &str,
+        mode: ParseMode,
+        rules: &Rules,
+        validation: Validation,
+    ) -> Result<Self, std::io::Error> {
+        let input = Input::read(filename)?;
+        let mut guide = Self::from_input(&input, mode, rules, validation)
+            .map_err(|e| e.in_file(input.name()))?;
+        guide.skipped = guide
+            .skipped
+            .into_iter()
+            .map(|e| e.in_file(input.name()))
+            .collect();
+
+        Ok(guide)
     }
 
-    fn is_win(&self, other: &Shape) -> Result {
-        use self::Result::*;
-        use Shape as S;
+    pub fn from_input(
+        input: &Input,
+        mode: ParseMode,
+        rules: &Rules,
+        validation: Validation,
+    ) -> Result<Self, ParseError> {
+        let mut guide = Guide {
+            rounds: Vec::new(),
+            skipped: Vec::new(),
+        };
 
-        match (*self, other) {
-            (S::Rock, S::Scissors) => Result::Win,
-            (S::Paper, S::Rock) => Result::Win,
-            (S::Scissors, S::Paper) => Result::Win,
-            (S::Rock, S::Paper) => Result::Loss,
-            (S::Scissors, S::Rock) => Result::Loss,
-            (S::Paper, S::Scissors) => Result::Loss,
-            _ => Draw,
+        for line in input.lines() {
+            match Round::from_line(&line, mode, rules) {
+                Ok(round) => guide.rounds.push(round),
+                Err(e) if validation == Validation::Lenient => guide.skipped.push(e),
+                Err(e) => return Err(e),
+            }
         }
-    }
 
-    fn from_char(c: char) -> Self {
-        match c {
-            'X' | 'A' => Shape::Rock,
-            'Y' | 'B' => Shape::Paper,
-            'Z' | 'C' => Shape::Scissors,
-            _ => panic!("Cannot parse Shape from character."),
-        }
+        Ok(guide)
     }
-}
 
-impl Result {
-    fn value(&self) -> u8 {
-        match self {
-            Result::Win => 6,
-            Result::Draw => 3,
-            Result::Loss => 0,
-        }
+    pub fn len(&self) -> usize {
+        self.rounds.len()
     }
 
-    fn from_char(c: char) -> Self {
-        match c {
-            'X' => Result::Loss,
-            'Y' => Result::Draw,
-            'Z' => Result::Win,
-            _ => panic!("Cannot parse Result from character."),
-        }
+    pub fn is_empty(&self) -> bool {
+        self.rounds.is_empty()
     }
 
-    fn get_shape_to_match_result(&self, their_shape: &Shape) -> Shape {
-        use self::Result as R;
-        use Shape as S;
+    /// Lines skipped in lenient mode, in input order.
+    pub fn skipped(&self) -> &[ParseError] {
+        &self.skipped
+    }
 
-        match (self, their_shape) {
-            (R::Win, S::Rock) => S::Paper,
-            (R::Win, S::Scissors) => S::Rock,
-            (R::Win, S::Paper) => S::Scissors,
-            (R::Loss, S::Rock) => S::Scissors,
-            (R::Loss, S::Scissors) => S::Paper,
-            (R::Loss, S::Paper) => S::Rock,
-            _ => *their_shape,
-        }
+    pub fn total_score(&self, rules: &Rules) -> u32 {
+        self.rounds
+            .iter()
+            .map(|(round, _)| round.score(rules))
+            .sum()
     }
 }
 
 impl Round {
-    fn from_line(l: &str, mode: &ParseMode) -> Self {
-        match mode {
-            ParseMode::Choice => Round {
-                theirs: Shape::from_char(l.chars().next().unwrap()),
-                mine: Shape::from_char(l.chars().nth(2).unwrap()),
-            },
-            ParseMode::Result => {
-                let temp_shape = Shape::from_char(l.chars().next().unwrap());
-                let result = Result::from_char(l.chars().nth(2).unwrap());
-                Round {
-                    mine: result.get_shape_to_match_result(&temp_shape),
-                    theirs: temp_shape,
-                }
+    fn from_line(line: &Line, mode: ParseMode, rules: &Rules) -> Result<(Self, char), ParseError> {
+        let their_letter = letter(line, 1, "opponent's", &rules.their_letters())?;
+        match line.text.chars().nth(1) {
+            Some(' ') => (),
+            None => return Err(line.error("column 2: missing my column")),
+            Some(c) => {
+                return Err(line.error(format!(
+                    "column 2: expected a single space between columns, found '{}'",
+                    c
+                )))
             }
         }
+        let my_letter = match mode {
+            ParseMode::Choice => letter(line, 3, "my", &rules.my_letters())?,
+            ParseMode::Result => letter(line, 3, "my", &rules.outcome_letters())?,
+        };
+        if let Some(c) = line.text.chars().nth(3) {
+            return Err(line.error(format!("column 4: unexpected '{}' after my column", c)));
+        }
+
+        let theirs = rules
+            .their_shape(their_letter)
+            .expect("Letter should be in the rules");
+        let mine = match mode {
+            ParseMode::Choice => rules.my_shape(my_letter),
+            ParseMode::Result => rules
+                .outcome(my_letter)
+                .map(|outcome| rules.shape_for(outcome, theirs)),
+        }
+        .expect("Letter should be in the rules");
+
+        Ok((Round { theirs, mine }, my_letter))
+    }
+
+    fn is_my_win(&self, rules: &Rules) -> Outcome {
+        rules.play(self.mine, self.theirs)
     }
 
-    fn is_my_win(&self) -> Result {
-        self.mine.is_win(&self.theirs)
+    fn score(&self, rules: &Rules) -> u32 {
+        let result = self.is_my_win(rules);
+        rules.shape_score(self.mine) + rules.outcome_score(result)
     }
+}
 
-    fn score(&self) -> u8 {
-        let result = self.is_my_win();
-        self.mine.value() + result.value()
+/// The letter at `column`, counting from 1, which must be one of `allowed`.
+fn letter(line: &Line, column: usize, name: &str, allowed: &[char]) -> Result<char, ParseError> {
+    let expected = allowed
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let error = |problem: String| {
+        line.error(format!(
+            "column {}: {}, expected one of {}",
+            column, problem, expected
+        ))
+    };
+
+    match line.text.chars().nth(column - 1) {
+        Some(c) if allowed.contains(&c) => Ok(c),
+        None => Err(error(format!("missing {} column", name))),
+        Some(c) if c.is_whitespace() => Err(error("unexpected whitespace".to_string())),
+        Some(c) if allowed.contains(&c.to_ascii_uppercase()) => {
+            Err(error(format!("lowercase letter '{}'", c)))
+        }
+        Some(c) => Err(error(format!("unknown letter '{}'", c))),
     }
 }
 
@@ -144,53 +190,153 @@ impl Round {
 mod tests {
     use super::*;
 
+    fn shape(name: &str) -> Shape {
+        Rules::rock_paper_scissors().shape(name).unwrap()
+    }
+
     #[test]
     fn test_round_from_line_parse() {
-        let line = String::from("A Z");
+        let line = Line {
+            number: 1,
+            text: "A Z",
+        };
+
+        let rules = Rules::rock_paper_scissors();
+
+        let (r, letter) = Round::from_line(&line, ParseMode::Choice, &rules).unwrap();
+
+        assert_eq!(shape("Rock"), r.theirs);
+        assert_eq!(shape("Scissors"), r.mine);
+        assert_eq!('Z', letter);
 
-        let r = Round::from_line(&line, &ParseMode::Choice);
+        let (r, _) = Round::from_line(&line, ParseMode::Result, &rules).unwrap();
 
-        assert_eq!(Shape::Rock, r.theirs);
-        assert_eq!(Shape::Scissors, r.mine);
+        assert_eq!(shape("Rock"), r.theirs);
+        assert_eq!(shape("Paper"), r.mine);
+    }
+
+    #[test]
+    fn test_round_from_line_errors() {
+        let rules = Rules::rock_paper_scissors();
+        let error = |text| {
+            let line = Line { number: 7, text };
+            Round::from_line(&line, ParseMode::Choice, &rules)
+                .unwrap_err()
+                .message
+        };
+
+        assert_eq!(
+            "column 1: missing opponent's column, expected one of A, B, C",
+            error("")
+        );
+        assert_eq!(
+            "column 1: unexpected whitespace, expected one of A, B, C",
+            error(" A X")
+        );
+        assert_eq!(
+            "column 1: lowercase letter 'a', expected one of A, B, C",
+            error("a X")
+        );
+        assert_eq!(
+            "column 1: unknown letter 'X', expected one of A, B, C",
+            error("X A")
+        );
+        assert_eq!("column 2: missing my column", error("A"));
+        assert_eq!(
+            "column 2: expected a single space between columns, found '\t'",
+            error("A\tX")
+        );
+        assert_eq!(
+            "column 3: unexpected whitespace, expected one of X, Y, Z",
+            error("A  X")
+        );
+        assert_eq!(
+            "column 3: missing my column, expected one of X, Y, Z",
+            error("A ")
+        );
+        assert_eq!(
+            "column 3: lowercase letter 'y', expected one of X, Y, Z",
+            error("A y")
+        );
+        assert_eq!("column 4: unexpected ' ' after my column", error("A Y "));
+    }
 
-        let r = Round::from_line(&line, &ParseMode::Result);
+    #[test]
+    fn test_guide_validation() {
+        let rules = Rules::rock_paper_scissors();
+        let input = Input::new("guide.txt", "A Y\nb X\nC Z\nC\n");
+
+        let error =
+            Guide::from_input(&input, ParseMode::Choice, &rules, Validation::Strict).unwrap_err();
+        assert_eq!(2, error.line);
+
+        let guide =
+            Guide::from_input(&input, ParseMode::Choice, &rules, Validation::Lenient).unwrap();
+        assert_eq!(2, guide.len());
+        assert_eq!(8 + 6, guide.total_score(&rules));
+        let skipped: Vec<usize> = guide.skipped().iter().map(|e| e.line).collect();
+        assert_eq!(vec![2, 4], skipped);
+    }
 
-        assert_eq!(Shape::Rock, r.theirs);
-        assert_eq!(Shape::Paper, r.mine);
+    #[test]
+    fn test_guide_with_rules_from_config() {
+        // the opponent plays R, P or S, and my column is a shape by the same letter, or an outcome
+        let rules = Rules::from_file("resources/test/02_rules.txt").unwrap();
+        let input = Input::new("guide.txt", "R P\nP R\nS S\n");
+
+        let guide =
+            Guide::from_input(&input, ParseMode::Choice, &rules, Validation::Strict).unwrap();
+        assert_eq!(15, guide.total_score(&rules));
+
+        let input = Input::new("guide.txt", "R D\nP L\nS W\n");
+        let guide =
+            Guide::from_input(&input, ParseMode::Result, &rules, Validation::Strict).unwrap();
+        assert_eq!(12, guide.total_score(&rules));
+
+        // letters from the puzzle's own guide aren't in these rules
+        let error = Guide::from_input(
+            &Input::new("guide.txt", "A Y"),
+            ParseMode::Choice,
+            &rules,
+            Validation::Strict,
+        );
+        assert!(error.is_err());
     }
 
     #[test]
     fn test_is_my_win() {
+        let rules = Rules::rock_paper_scissors();
+
         let round1 = Round {
-            theirs: Shape::Rock,
-            mine: Shape::Paper,
+            theirs: shape("Rock"),
+            mine: shape("Paper"),
         };
 
-        assert_eq!(Result::Win, round1.is_my_win());
+        assert_eq!(Outcome::Win, round1.is_my_win(&rules));
 
         let round2 = Round {
-            theirs: Shape::Rock,
-            mine: Shape::Scissors,
+            theirs: shape("Rock"),
+            mine: shape("Scissors"),
         };
 
-        assert_eq!(Result::Loss, round2.is_my_win());
+        assert_eq!(Outcome::Loss, round2.is_my_win(&rules));
 
         let round3 = Round {
-            theirs: Shape::Paper,
-            mine: Shape::Paper,
+            theirs: shape("Paper"),
+            mine: shape("Paper"),
         };
 
-        assert_eq!(Result::Draw, round3.is_my_win());
+        assert_eq!(Outcome::Draw, round3.is_my_win(&rules));
     }
 
     #[test]
     fn test_round_score() {
         let round1 = Round {
-            theirs: Shape::Rock,
-            mine: Shape::Paper,
+            theirs: shape("Rock"),
+            mine: shape("Paper"),
         };
 
-        assert_eq!(8, round1.score());
+        assert_eq!(8, round1.score(&Rules::rock_paper_scissors()));
     }
 
     #[test]
@@ -203,4 +349,17 @@ mod tests {
         let result = calculate_score_for_file(filename, ParseMode::Result);
         assert_eq!(12, result);
     }
+
+    #[test]
+    fn test_sum_scores_with_other_rules() {
+        // with no Lizard or Spock in the guide, every round plays out as in Rock Paper Scissors
+        let filename = "resources/test/02_rps.txt";
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+
+        let result = calculate_score_for_file_with_rules(filename, ParseMode::Choice, &rules);
+        assert_eq!(15, result);
+
+        let result = calculate_score_for_file_with_rules(filename, ParseMode::Result, &rules);
+        assert_eq!(12, result);
+    }
 }
@@ -0,0 +1,307 @@
+use super::rules::{Outcome, Rules, Shape};
+use super::{Guide, ParseMode, Validation};
+
+/// Most shapes for which every mapping of my column is tried. There are n! mappings of n shapes, so a few
+/// more would take too long and too much memory.
+pub const MAX_MAPPED_SHAPES: usize = 7;
+
+/// How a strategy guide plays out, and how it compares with the best it could have done.
+///
+/// Besides the rounds as the guide says to play them, every way of reading my column's letters as
+/// shapes is scored, so a guide can be compared with the one it might have meant.
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub rounds: Vec<RoundReport>,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub total: u32,
+    /// Total if the best shape had been played in every round.
+    pub optimal_total: u32,
+    /// Letters used for shapes in my column, which each `Mapping` gives a shape for.
+    pub letters: Vec<char>,
+    /// Total for every mapping of `letters` to shapes. The mapping in the rules comes first. Empty if my
+    /// column has letters that aren't shapes, as it can when read as outcomes, or if the mappings were
+    /// skipped.
+    pub mappings: Vec<Mapping>,
+    /// True if the rules have more than `MAX_MAPPED_SHAPES` shapes, so the mappings weren't tried.
+    pub mappings_skipped: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundReport {
+    pub theirs: Shape,
+    pub mine: Shape,
+    pub outcome: Outcome,
+    pub score: u32,
+    /// Score for the best shape to play against `theirs`.
+    pub best_score: u32,
+}
+
+/// One way of reading my column: `shapes[i]` is played for `letters[i]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub shapes: Vec<Shape>,
+    pub total: u32,
+}
+
+#[allow(dead_code)]
+impl Analysis {
+    pub fn from_file(filename: &str, mode: ParseMode, rules: &Rules) -> Self {
+        let guide = Guide::from_file(filename, mode, rules, Validation::Strict)
+            .expect("Could not read strategy guide");
+        Self::from_guide(&guide, rules)
+    }
+
+    pub fn from_guide(guide: &Guide, rules: &Rules) -> Self {
+        let reports: Vec<RoundReport> = guide
+            .rounds
+            .iter()
+            .map(|(round, _)| RoundReport {
+                theirs: round.theirs,
+                mine: round.mine,
+                outcome: round.is_my_win(rules),
+                score: round.score(rules),
+                best_score: best_score(rules, round.theirs),
+            })
+            .collect();
+
+        let letters = rules.my_letters();
+        // position of each round's letter in `letters`, if they are all shape letters
+        let positions: Option<Vec<(Shape, usize)>> = guide
+            .rounds
+            .iter()
+            .map(|(round, letter)| Some((round.theirs, letters.iter().position(|l| l == letter)?)))
+            .collect();
+
+        let shapes: Vec<Shape> = rules.shapes().collect();
+        let mappings_skipped = shapes.len() > MAX_MAPPED_SHAPES;
+        let mappings = match positions {
+            Some(_) if mappings_skipped => Vec::new(),
+            Some(positions) => permutations(&shapes)
+                .into_iter()
+                .map(|shapes| Mapping {
+                    total: positions
+                        .iter()
+                        .map(|(theirs, index)| rules.score(shapes[*index], *theirs))
+                        .sum(),
+                    shapes,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let count = |outcome| reports.iter().filter(|r| r.outcome == outcome).count();
+
+        Analysis {
+            wins: count(Outcome::Win),
+            draws: count(Outcome::Draw),
+            losses: count(Outcome::Loss),
+            total: reports.iter().map(|r| r.score).sum(),
+            optimal_total: reports.iter().map(|r| r.best_score).sum(),
+            rounds: reports,
+            letters,
+            mappings,
+            mappings_skipped,
+        }
+    }
+
+    /// How many points the guide falls short of the best possible total.
+    pub fn distance_from_optimal(&self) -> u32 {
+        self.optimal_total - self.total
+    }
+
+    /// A summary of the guide, followed by the total for every mapping of my column. Shapes are named
+    /// from `rules`, which should be the rules the analysis was made with.
+    pub fn report(&self, rules: &Rules) -> String {
+        let mut lines = vec![
+            format!(
+                "{} rounds: {} won, {} drawn, {} lost",
+                self.rounds.len(),
+                self.wins,
+                self.draws,
+                self.losses
+            ),
+            format!(
+                "Total score {} of a possible {} ({} short)",
+                self.total,
+                self.optimal_total,
+                self.distance_from_optimal()
+            ),
+        ];
+
+        if self.mappings_skipped {
+            lines.push(format!(
+                "  Mappings not tried: {} shapes is more than {}",
+                self.letters.len(),
+                MAX_MAPPED_SHAPES
+            ));
+        }
+        for mapping in self.mappings.iter() {
+            let shapes: Vec<String> = self
+                .letters
+                .iter()
+                .zip(mapping.shapes.iter())
+                .map(|(letter, shape)| format!("{}={}", letter, rules.name(*shape)))
+                .collect();
+            lines.push(format!("  {}: {}", shapes.join(" "), mapping.total));
+        }
+
+        lines.join("\n")
+    }
+
+    /// The mapping of my column with the highest total. With equal totals, the first is used.
+    pub fn best_mapping(&self) -> Option<&Mapping> {
+        self.mappings.iter().rev().max_by_key(|m| m.total)
+    }
+}
+
+fn best_score(rules: &Rules, theirs: Shape) -> u32 {
+    rules
+        .shapes()
+        .map(|mine| rules.score(mine, theirs))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Every ordering of `items`, starting with `items` as given.
+fn permutations<T: Copy>(items: &[T]) -> Vec<Vec<T>> {
+    if items.is_empty() {
+        return vec![Vec::new()];
+    }
+
+    let mut result = Vec::new();
+    for (i, first) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(i);
+        for mut tail in permutations(&rest) {
+            tail.insert(0, *first);
+            result.push(tail);
+        }
+    }
+
+    result
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILENAME: &str = "resources/test/02_rps.txt";
+
+    #[test]
+    fn test_analysis_by_choice() {
+        let rules = Rules::rock_paper_scissors();
+        let analysis = Analysis::from_file(FILENAME, ParseMode::Choice, &rules);
+
+        let scores: Vec<u32> = analysis.rounds.iter().map(|r| r.score).collect();
+        assert_eq!(vec![8, 1, 6], scores);
+        assert_eq!((1, 1, 1), (analysis.wins, analysis.draws, analysis.losses));
+        assert_eq!(15, analysis.total);
+        assert_eq!(8 + 9 + 7, analysis.optimal_total);
+        assert_eq!(9, analysis.distance_from_optimal());
+    }
+
+    #[test]
+    fn test_analysis_by_result() {
+        let rules = Rules::rock_paper_scissors();
+        let analysis = Analysis::from_file(FILENAME, ParseMode::Result, &rules);
+
+        assert_eq!((1, 1, 1), (analysis.wins, analysis.draws, analysis.losses));
+        assert_eq!(12, analysis.total);
+        assert_eq!(12, analysis.distance_from_optimal());
+    }
+
+    #[test]
+    fn test_mappings() {
+        let rules = Rules::rock_paper_scissors();
+        let shape = |name| rules.shape(name).unwrap();
+        let analysis = Analysis::from_file(FILENAME, ParseMode::Result, &rules);
+
+        assert_eq!(vec!['X', 'Y', 'Z'], analysis.letters);
+        assert_eq!(6, analysis.mappings.len());
+        // the mapping in the rules comes first, whatever the mode
+        assert_eq!(15, analysis.mappings[0].total);
+
+        let best = analysis.best_mapping().unwrap();
+        assert_eq!(
+            vec![shape("Scissors"), shape("Paper"), shape("Rock")],
+            best.shapes
+        );
+        assert_eq!(analysis.optimal_total, best.total);
+    }
+
+    #[test]
+    fn test_report() {
+        let rules = Rules::rock_paper_scissors();
+        let report = Analysis::from_file(FILENAME, ParseMode::Choice, &rules).report(&rules);
+        let lines: Vec<&str> = report.lines().collect();
+
+        assert_eq!(8, lines.len());
+        assert_eq!("3 rounds: 1 won, 1 drawn, 1 lost", lines[0]);
+        assert_eq!("Total score 15 of a possible 24 (9 short)", lines[1]);
+        assert_eq!("  X=Rock Y=Paper Z=Scissors: 15", lines[2]);
+    }
+
+    #[test]
+    fn test_mappings_with_more_shapes() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let analysis = Analysis::from_file(FILENAME, ParseMode::Choice, &rules);
+
+        assert_eq!(120, analysis.mappings.len());
+        assert_eq!(15, analysis.total);
+        // Spock beats Rock and Scissors, and Lizard beats Paper, all for more than the usual answers
+        assert_eq!(11 + 10 + 11, analysis.optimal_total);
+    }
+
+    #[test]
+    fn test_mappings_skipped_with_many_shapes() {
+        // each shape beats the next four round the circle, and loses to the four before it
+        let names = ["A", "B", "C", "D", "E", "F", "G", "H", "I"];
+        let mut builder = Rules::builder();
+        for (i, name) in names.iter().enumerate() {
+            let their_letter = name.chars().next().unwrap();
+            let my_letter = (b'R' + i as u8) as char;
+            builder = builder.with_shape(name, their_letter, my_letter, i as u32 + 1);
+            for step in 1..=4 {
+                builder = builder.with_beats(name, names[(i + step) % names.len()]);
+            }
+        }
+        let rules = builder.with_outcome_letters('X', 'Y', 'Z').build().unwrap();
+
+        let analysis = Analysis::from_file(FILENAME, ParseMode::Choice, &rules);
+        assert!(analysis.mappings_skipped);
+        assert!(analysis.mappings.is_empty());
+        assert_eq!(None, analysis.best_mapping());
+        assert_eq!(3, analysis.rounds.len());
+
+        let report = analysis.report(&rules);
+        assert_eq!(
+            Some("  Mappings not tried: 9 shapes is more than 7"),
+            report.lines().nth(2)
+        );
+
+        let analysis =
+            Analysis::from_file(FILENAME, ParseMode::Choice, &Rules::rock_paper_scissors());
+        assert!(!analysis.mappings_skipped);
+    }
+
+    #[test]
+    fn test_permutations() {
+        assert_eq!(
+            vec![
+                vec![1, 2, 3],
+                vec![1, 3, 2],
+                vec![2, 1, 3],
+                vec![2, 3, 1],
+                vec![3, 1, 2],
+                vec![3, 2, 1]
+            ],
+            permutations(&[1, 2, 3])
+        );
+        assert_eq!(vec![Vec::<u8>::new()], permutations(&[]));
+    }
+}
@@ -0,0 +1,600 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::common::io::Input;
+use crate::common::str::{Line, ParseError};
+
+/// A shape that can be played, as an index into the `Rules` it belongs to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Shape(usize);
+
+/// The result of a round for the player whose shape comes first.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+/// The rules of a game where two players each show a shape, and some shapes beat others. The game is
+/// all data: the shapes, the letters used for them in a strategy guide, which shape beats which, and how
+/// rounds are scored.
+///
+/// Rules are made with a `RulesBuilder`, which checks that they are consistent. Every pair of different
+/// shapes must have exactly one winner, and every shape must both beat and lose to another, so that each
+/// outcome can be played against any shape.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    shapes: Vec<ShapeRule>,
+    beats: HashSet<(Shape, Shape)>,
+    outcome_letters: Vec<(char, Outcome)>,
+    outcome_scores: [(Outcome, u32); 3],
+}
+
+#[derive(Debug, Clone)]
+struct ShapeRule {
+    name: String,
+    their_letter: char,
+    my_letter: char,
+    score: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RulesBuilder {
+    shapes: Vec<ShapeRule>,
+    beats: Vec<(String, String)>,
+    outcome_letters: Vec<(char, Outcome)>,
+    outcome_scores: [(Outcome, u32); 3],
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RulesError {
+    TooFewShapes,
+    DuplicateName(String),
+    DuplicateLetter(char),
+    UnknownShape(String),
+    BeatsItself(String),
+    BeatsBothWays(String, String),
+    NoWinner(String, String),
+    /// A shape that beats every other shape, or loses to every other shape, so some outcomes can't be
+    /// played against it.
+    OneSided(String),
+    MissingOutcomeLetter(Outcome),
+}
+
+/// A rules file that can't be read, or that describes inconsistent rules.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ConfigError {
+    Parse(ParseError),
+    Rules(RulesError),
+}
+
+#[allow(dead_code)]
+impl Rules {
+    pub fn builder() -> RulesBuilder {
+        RulesBuilder {
+            shapes: Vec::new(),
+            beats: Vec::new(),
+            outcome_letters: Vec::new(),
+            outcome_scores: [(Outcome::Win, 6), (Outcome::Draw, 3), (Outcome::Loss, 0)],
+        }
+    }
+
+    /// Rock, Paper, Scissors as scored in the puzzle. The opponent plays A, B or C. The second column is
+    /// X, Y or Z, which is either a shape or, read as an outcome, loss, draw or win.
+    pub fn rock_paper_scissors() -> Self {
+        Rules::builder()
+            .with_shape("Rock", 'A', 'X', 1)
+            .with_shape("Paper", 'B', 'Y', 2)
+            .with_shape("Scissors", 'C', 'Z', 3)
+            .with_beats("Rock", "Scissors")
+            .with_beats("Paper", "Rock")
+            .with_beats("Scissors", "Paper")
+            .with_outcome_letters('X', 'Y', 'Z')
+            .build()
+            .expect("Preset rules should be consistent")
+    }
+
+    /// Rock, Paper, Scissors, Lizard, Spock. The first three shapes use the same letters as in
+    /// `rock_paper_scissors`, with Lizard as D/W and Spock as E/V.
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Rules::builder()
+            .with_shape("Rock", 'A', 'X', 1)
+            .with_shape("Paper", 'B', 'Y', 2)
+            .with_shape("Scissors", 'C', 'Z', 3)
+            .with_shape("Lizard", 'D', 'W', 4)
+            .with_shape("Spock", 'E', 'V', 5)
+            .with_beats("Scissors", "Paper")
+            .with_beats("Paper", "Rock")
+            .with_beats("Rock", "Lizard")
+            .with_beats("Lizard", "Spock")
+            .with_beats("Spock", "Scissors")
+            .with_beats("Scissors", "Lizard")
+            .with_beats("Lizard", "Paper")
+            .with_beats("Paper", "Spock")
+            .with_beats("Spock", "Rock")
+            .with_beats("Rock", "Scissors")
+            .with_outcome_letters('X', 'Y', 'Z')
+            .build()
+            .expect("Preset rules should be consistent")
+    }
+
+    /// Rules from a run config file. See `from_config`.
+    pub fn from_file(filename: &str) -> Result<Self, std::io::Error> {
+        let input = Input::read(filename)?;
+        Ok(Self::from_config(&input).map_err(|e| match e {
+            ConfigError::Parse(e) => ConfigError::Parse(e.in_file(input.name())),
+            e => e,
+        })?)
+    }
+
+    /// Rules from a run config, one setting per line. Blank lines and lines starting with `#` are ignored.
+    ///
+    /// ```text
+    /// shape Rock A X 1      name, letter in the opponent's column, letter in my column, score
+    /// beats Rock Scissors   winner, loser
+    /// outcomes X Y Z        letters in my column for a loss, a draw and a win
+    /// scores 6 3 0          scores for a win, a draw and a loss
+    /// ```
+    pub fn from_config(input: &Input) -> Result<Self, ConfigError> {
+        let mut builder = Rules::builder();
+
+        for line in input.lines() {
+            let setting = line.text.trim();
+            if setting.is_empty() || setting.starts_with('#') {
+                continue;
+            }
+
+            let line = Line {
+                number: line.number,
+                text: setting,
+            };
+            builder = match setting.split_once(' ').map(|(word, _)| word) {
+                Some("shape") => {
+                    let (name, theirs, mine, score): (String, char, char, u32) =
+                        line.scan("shape {} {} {} {}")?;
+                    builder.with_shape(&name, theirs, mine, score)
+                }
+                Some("beats") => {
+                    let (winner, loser): (String, String) = line.scan("beats {} {}")?;
+                    builder.with_beats(&winner, &loser)
+                }
+                Some("outcomes") => {
+                    let (loss, draw, win) = line.scan("outcomes {} {} {}")?;
+                    builder.with_outcome_letters(loss, draw, win)
+                }
+                Some("scores") => {
+                    let (win, draw, loss) = line.scan("scores {} {} {}")?;
+                    builder.with_outcome_scores(win, draw, loss)
+                }
+                _ => return Err(line.error("unknown setting").into()),
+            };
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// Every shape, in the order they were added.
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.shapes.len()).map(Shape)
+    }
+
+    pub fn shape(&self, name: &str) -> Option<Shape> {
+        self.shapes.iter().position(|s| s.name == name).map(Shape)
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.shapes[shape.0].name
+    }
+
+    pub fn shape_score(&self, shape: Shape) -> u32 {
+        self.shapes[shape.0].score
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        self.outcome_scores
+            .iter()
+            .find(|(o, _)| *o == outcome)
+            .map(|(_, score)| *score)
+            .expect("Every outcome has a score")
+    }
+
+    /// Shape for a letter in the opponent's column.
+    pub fn their_shape(&self, letter: char) -> Option<Shape> {
+        self.shapes
+            .iter()
+            .position(|s| s.their_letter == letter)
+            .map(Shape)
+    }
+
+    /// Shape for a letter in my column, when it is read as a shape.
+    pub fn my_shape(&self, letter: char) -> Option<Shape> {
+        self.shapes
+            .iter()
+            .position(|s| s.my_letter == letter)
+            .map(Shape)
+    }
+
+    /// Outcome for a letter in my column, when it is read as an outcome.
+    pub fn outcome(&self, letter: char) -> Option<Outcome> {
+        self.outcome_letters
+            .iter()
+            .find(|(l, _)| *l == letter)
+            .map(|(_, outcome)| *outcome)
+    }
+
+    /// Letters used for shapes in the opponent's column, in shape order.
+    pub fn their_letters(&self) -> Vec<char> {
+        self.shapes.iter().map(|s| s.their_letter).collect()
+    }
+
+    /// Letters used for outcomes in my column, for a loss, a draw and a win.
+    pub fn outcome_letters(&self) -> Vec<char> {
+        self.outcome_letters.iter().map(|(l, _)| *l).collect()
+    }
+
+    /// Letters used for shapes in my column, in shape order.
+    pub fn my_letters(&self) -> Vec<char> {
+        self.shapes.iter().map(|s| s.my_letter).collect()
+    }
+
+    /// Outcome of playing `mine` against `theirs`.
+    pub fn play(&self, mine: Shape, theirs: Shape) -> Outcome {
+        if self.beats.contains(&(mine, theirs)) {
+            Outcome::Win
+        } else if self.beats.contains(&(theirs, mine)) {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// A shape to play against `theirs` to get `outcome`. When several shapes would do, the first is used.
+    pub fn shape_for(&self, outcome: Outcome, theirs: Shape) -> Shape {
+        self.shapes()
+            .find(|mine| self.play(*mine, theirs) == outcome)
+            .expect("Every pair of shapes has a winner, so every outcome is possible")
+    }
+
+    /// Score for the player of `mine`: the score of their shape plus the score of the outcome.
+    pub fn score(&self, mine: Shape, theirs: Shape) -> u32 {
+        self.shape_score(mine) + self.outcome_score(self.play(mine, theirs))
+    }
+}
+
+#[allow(dead_code)]
+impl RulesBuilder {
+    /// Add a shape with the letters used for it in each column and the score for playing it.
+    pub fn with_shape(
+        mut self,
+        name: &str,
+        their_letter: char,
+        my_letter: char,
+        score: u32,
+    ) -> Self {
+        self.shapes.push(ShapeRule {
+            name: name.to_string(),
+            their_letter,
+            my_letter,
+            score,
+        });
+        self
+    }
+
+    pub fn with_beats(mut self, winner: &str, loser: &str) -> Self {
+        self.beats.push((winner.to_string(), loser.to_string()));
+        self
+    }
+
+    /// Letters for each outcome when my column is read as the outcome wanted.
+    pub fn with_outcome_letters(mut self, loss: char, draw: char, win: char) -> Self {
+        self.outcome_letters = vec![
+            (loss, Outcome::Loss),
+            (draw, Outcome::Draw),
+            (win, Outcome::Win),
+        ];
+        self
+    }
+
+    pub fn with_outcome_scores(mut self, win: u32, draw: u32, loss: u32) -> Self {
+        self.outcome_scores = [
+            (Outcome::Win, win),
+            (Outcome::Draw, draw),
+            (Outcome::Loss, loss),
+        ];
+        self
+    }
+
+    pub fn build(self) -> Result<Rules, RulesError> {
+        if self.shapes.len() < 2 {
+            return Err(RulesError::TooFewShapes);
+        }
+
+        for (i, shape) in self.shapes.iter().enumerate() {
+            let earlier = &self.shapes[..i];
+            if earlier.iter().any(|s| s.name == shape.name) {
+                return Err(RulesError::DuplicateName(shape.name.clone()));
+            }
+            if earlier.iter().any(|s| s.their_letter == shape.their_letter) {
+                return Err(RulesError::DuplicateLetter(shape.their_letter));
+            }
+            if earlier.iter().any(|s| s.my_letter == shape.my_letter) {
+                return Err(RulesError::DuplicateLetter(shape.my_letter));
+            }
+        }
+
+        for (i, (letter, _)) in self.outcome_letters.iter().enumerate() {
+            if self.outcome_letters[..i].iter().any(|(l, _)| l == letter) {
+                return Err(RulesError::DuplicateLetter(*letter));
+            }
+        }
+        for outcome in [Outcome::Loss, Outcome::Draw, Outcome::Win] {
+            if !self.outcome_letters.iter().any(|(_, o)| *o == outcome) {
+                return Err(RulesError::MissingOutcomeLetter(outcome));
+            }
+        }
+
+        let find = |name: &String| {
+            self.shapes
+                .iter()
+                .position(|s| s.name == *name)
+                .map(Shape)
+                .ok_or_else(|| RulesError::UnknownShape(name.clone()))
+        };
+
+        let mut beats = HashSet::new();
+        for (winner, loser) in self.beats.iter() {
+            let pair = (find(winner)?, find(loser)?);
+            if pair.0 == pair.1 {
+                return Err(RulesError::BeatsItself(winner.clone()));
+            }
+            if beats.contains(&(pair.1, pair.0)) {
+                return Err(RulesError::BeatsBothWays(winner.clone(), loser.clone()));
+            }
+            beats.insert(pair);
+        }
+
+        for (i, first) in self.shapes.iter().enumerate() {
+            for (j, second) in self.shapes.iter().enumerate().skip(i + 1) {
+                if !beats.contains(&(Shape(i), Shape(j))) && !beats.contains(&(Shape(j), Shape(i)))
+                {
+                    return Err(RulesError::NoWinner(
+                        first.name.clone(),
+                        second.name.clone(),
+                    ));
+                }
+            }
+        }
+
+        for (i, shape) in self.shapes.iter().enumerate() {
+            let wins = beats.iter().any(|(winner, _)| *winner == Shape(i));
+            let loses = beats.iter().any(|(_, loser)| *loser == Shape(i));
+            if !wins || !loses {
+                return Err(RulesError::OneSided(shape.name.clone()));
+            }
+        }
+
+        Ok(Rules {
+            shapes: self.shapes,
+            beats,
+            outcome_letters: self.outcome_letters,
+            outcome_scores: self.outcome_scores,
+        })
+    }
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::TooFewShapes => write!(f, "a game needs at least two shapes"),
+            RulesError::DuplicateName(name) => write!(f, "more than one shape is called {}", name),
+            RulesError::DuplicateLetter(c) => write!(f, "letter {} is used more than once", c),
+            RulesError::UnknownShape(name) => write!(f, "there is no shape called {}", name),
+            RulesError::BeatsItself(name) => write!(f, "{} cannot beat itself", name),
+            RulesError::BeatsBothWays(a, b) => write!(f, "{} and {} both beat each other", a, b),
+            RulesError::NoWinner(a, b) => write!(f, "neither of {} and {} beats the other", a, b),
+            RulesError::OneSided(name) => {
+                write!(f, "{} must both beat and lose to other shapes", name)
+            }
+            RulesError::MissingOutcomeLetter(o) => write!(f, "no letter for outcome {:?}", o),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+impl From<ParseError> for ConfigError {
+    fn from(error: ParseError) -> Self {
+        ConfigError::Parse(error)
+    }
+}
+
+impl From<RulesError> for ConfigError {
+    fn from(error: RulesError) -> Self {
+        ConfigError::Rules(error)
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Parse(e) => write!(f, "{}", e),
+            ConfigError::Rules(e) => write!(f, "inconsistent rules: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ConfigError> for std::io::Error {
+    fn from(error: ConfigError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rock_paper_scissors() {
+        let rules = Rules::rock_paper_scissors();
+        let rock = rules.shape("Rock").unwrap();
+        let paper = rules.shape("Paper").unwrap();
+        let scissors = rules.shape("Scissors").unwrap();
+
+        assert_eq!(Some(rock), rules.their_shape('A'));
+        assert_eq!(Some(scissors), rules.my_shape('Z'));
+        assert_eq!(None, rules.my_shape('A'));
+        assert_eq!(Some(Outcome::Draw), rules.outcome('Y'));
+
+        assert_eq!(Outcome::Win, rules.play(paper, rock));
+        assert_eq!(Outcome::Loss, rules.play(scissors, rock));
+        assert_eq!(Outcome::Draw, rules.play(paper, paper));
+
+        assert_eq!(paper, rules.shape_for(Outcome::Win, rock));
+        assert_eq!(scissors, rules.shape_for(Outcome::Loss, rock));
+        assert_eq!(8, rules.score(paper, rock));
+    }
+
+    #[test]
+    fn test_rock_paper_scissors_lizard_spock() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let shape = |name| rules.shape(name).unwrap();
+
+        assert_eq!(5, rules.shapes().count());
+        // every shape beats exactly two others
+        for mine in rules.shapes() {
+            let wins = rules
+                .shapes()
+                .filter(|theirs| rules.play(mine, *theirs) == Outcome::Win)
+                .count();
+            assert_eq!(2, wins, "{}", rules.name(mine));
+        }
+
+        assert_eq!(Outcome::Win, rules.play(shape("Spock"), shape("Rock")));
+        assert_eq!(Outcome::Loss, rules.play(shape("Lizard"), shape("Rock")));
+        assert_eq!(Some(shape("Spock")), rules.their_shape('E'));
+        assert_eq!(
+            shape("Paper"),
+            rules.shape_for(Outcome::Win, shape("Spock"))
+        );
+        assert_eq!(4 + 6, rules.score(shape("Lizard"), shape("Spock")));
+    }
+
+    #[test]
+    fn test_rules_from_config() {
+        let rules = Rules::from_file("resources/02_rules.txt").unwrap();
+        let preset = Rules::rock_paper_scissors();
+
+        assert_eq!(preset.their_letters(), rules.their_letters());
+        assert_eq!(preset.my_letters(), rules.my_letters());
+        assert_eq!(vec!['X', 'Y', 'Z'], rules.outcome_letters());
+        for mine in rules.shapes() {
+            for theirs in rules.shapes() {
+                assert_eq!(preset.score(mine, theirs), rules.score(mine, theirs));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bad_config() {
+        let config = |text: &str| Rules::from_config(&Input::new("rules.txt", text));
+
+        let error = config("shape Rock A X 1\n\nshape Paper B Y two\n").unwrap_err();
+        assert!(matches!(error, ConfigError::Parse(e) if e.line == 3));
+
+        let error = config("# comment\nshapes Rock A X 1\n").unwrap_err();
+        assert!(matches!(error, ConfigError::Parse(e) if e.line == 2));
+
+        let error = config("shape Rock A X 1\nshape Paper B Y 2\noutcomes X Y Z\n").unwrap_err();
+        assert_eq!(
+            ConfigError::Rules(RulesError::NoWinner("Rock".into(), "Paper".into())),
+            error
+        );
+
+        // Rock beats everything, so a guide asking to lose to it couldn't be played
+        let error = config(
+            "shape Rock R R 1\nshape Paper P P 2\nshape Scissors S S 3\n\
+             beats Rock Scissors\nbeats Rock Paper\nbeats Scissors Paper\noutcomes L D W\n",
+        )
+        .unwrap_err();
+        assert_eq!(
+            ConfigError::Rules(RulesError::OneSided("Rock".into())),
+            error
+        );
+    }
+
+    #[test]
+    fn test_inconsistent_rules() {
+        let base = || {
+            Rules::builder()
+                .with_shape("Rock", 'A', 'X', 1)
+                .with_shape("Paper", 'B', 'Y', 2)
+                .with_outcome_letters('X', 'Y', 'Z')
+        };
+
+        // with two shapes, one of them always beats all the others
+        assert_eq!(
+            RulesError::OneSided("Rock".into()),
+            base().with_beats("Paper", "Rock").build().unwrap_err()
+        );
+        assert_eq!(
+            RulesError::NoWinner("Rock".into(), "Paper".into()),
+            base().build().unwrap_err()
+        );
+        assert_eq!(
+            RulesError::BeatsBothWays("Rock".into(), "Paper".into()),
+            base()
+                .with_beats("Paper", "Rock")
+                .with_beats("Rock", "Paper")
+                .build()
+                .unwrap_err()
+        );
+        assert_eq!(
+            RulesError::UnknownShape("Stone".into()),
+            base().with_beats("Stone", "Paper").build().unwrap_err()
+        );
+        assert_eq!(
+            RulesError::BeatsItself("Rock".into()),
+            base().with_beats("Rock", "Rock").build().unwrap_err()
+        );
+        assert_eq!(
+            RulesError::DuplicateLetter('A'),
+            base()
+                .with_shape("Scissors", 'A', 'Z', 3)
+                .build()
+                .unwrap_err()
+        );
+        assert_eq!(
+            RulesError::TooFewShapes,
+            Rules::builder()
+                .with_shape("Rock", 'A', 'X', 1)
+                .build()
+                .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_shape_that_beats_all_others() {
+        let error = Rules::builder()
+            .with_shape("Rock", 'A', 'X', 1)
+            .with_shape("Paper", 'B', 'Y', 2)
+            .with_shape("Scissors", 'C', 'Z', 3)
+            .with_beats("Rock", "Paper")
+            .with_beats("Rock", "Scissors")
+            .with_beats("Paper", "Scissors")
+            .with_outcome_letters('X', 'Y', 'Z')
+            .build()
+            .unwrap_err();
+
+        assert_eq!(RulesError::OneSided("Rock".into()), error);
+        assert_eq!(
+            "Rock must both beat and lose to other shapes",
+            error.to_string()
+        );
+    }
+}
@@ -1,4 +1,13 @@
-use crate::common::io;
+use crate::common::io::Input;
+use crate::common::str::{Line, ParseError};
+
+pub mod groups;
+pub mod items;
+pub mod priority;
+
+use groups::{GroupError, Grouping};
+use items::ItemSet;
+use priority::{AocScheme, PriorityScheme};
 
 pub fn get_sum_of_priorities_for_common_items(rucksacks: &[Rucksack]) -> usize {
     rucksacks.iter().fold(0, |acc, rs: &Rucksack| -> usize {
@@ -6,89 +15,94 @@ pub fn get_sum_of_priorities_for_common_items(rucksacks: &[Rucksack]) -> usize {
     })
 }
 
-pub fn get_sum_of_priorities_for_group(rucksacks: &[Rucksack]) -> usize {
-    rucksacks
-        .chunks(3)
-        .map(|rs: &[Rucksack]| rs[0].group_item_value())
-        .sum()
+pub fn get_sum_of_priorities_for_group(
+    rucksacks: &[Rucksack],
+    grouping: Grouping,
+) -> Result<usize, GroupError> {
+    groups::group(rucksacks, grouping).badge_priority_sum()
 }
 
-pub fn get_rucksacks_from_file(filename: &str) -> Vec<Rucksack> {
-    let lines = io::read_file_as_vector(filename).expect("Could not read file");
-
-    lines
-        .chunks(3)
-        .flat_map(|ch: &[String]| -> Vec<Rucksack> {
-            let common = get_common_char(vec![&ch[0], &ch[1], &ch[2]]);
-
-            ch.iter()
-                .map(|l: &String| -> Rucksack {
-                    let mut rs = Rucksack::from_string(l);
-                    rs.add_group_value(common.unwrap());
-
-                    rs
-                })
-                .collect::<Vec<Rucksack>>()
-        })
-        .collect::<Vec<Rucksack>>()
+pub fn get_rucksacks_from_file(filename: &str) -> Result<Vec<Rucksack<'static>>, std::io::Error> {
+    get_rucksacks_from_file_with_scheme(filename, &AocScheme)
 }
 
-mod priority {
-    /// Priority of letter related to index of array.
-    const PRIORITIES: [char; 53] = [
-        '_', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q',
-        'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I',
-        'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
-    ];
-
-    pub fn get_priority(c: char) -> usize {
-        let priority = PRIORITIES.iter().position(|&v| v == c);
+/// Rucksacks whose items are those of `scheme`. Any other character is an error.
+pub fn get_rucksacks_from_file_with_scheme<'a>(
+    filename: &str,
+    scheme: &'a dyn PriorityScheme,
+) -> Result<Vec<Rucksack<'a>>, std::io::Error> {
+    let input = Input::read(filename)?;
+    Ok(parse_rucksacks(&input, scheme).map_err(|e| e.in_file(input.name()))?)
+}
 
-        priority.expect("Character is not in priority list.")
-    }
+fn parse_rucksacks<'a>(
+    input: &Input,
+    scheme: &'a dyn PriorityScheme,
+) -> Result<Vec<Rucksack<'a>>, ParseError> {
+    input
+        .lines()
+        .map(|l| Rucksack::from_line(&l, scheme))
+        .collect()
 }
 
+/// A rucksack's two compartments, each holding half of its items. Exactly one item is in both.
 #[derive(Debug)]
-pub struct Rucksack {
+pub struct Rucksack<'a> {
+    compartments: [ItemSet<'a>; 2],
     common_item: char,
-    group_common: Option<char>,
 }
 
-impl Rucksack {
-    fn from_string(line: &str) -> Self {
-        let (first, second) = line.split_at(line.len() / 2);
-
-        Rucksack {
-            common_item: get_common_char(vec![first, second]).unwrap(),
-            group_common: None,
+#[allow(dead_code)]
+impl<'a> Rucksack<'a> {
+    fn from_line(line: &Line, scheme: &'a dyn PriorityScheme) -> Result<Self, ParseError> {
+        // a line may hold any characters, so count and split by characters rather than bytes
+        let count = line.text.chars().count();
+        if !count.is_multiple_of(2) {
+            return Err(line.error(format!(
+                "{} items can't be split evenly between two compartments",
+                count
+            )));
         }
-    }
 
-    fn common_item_value(&self) -> usize {
-        priority::get_priority(self.common_item)
+        let middle = line
+            .text
+            .char_indices()
+            .nth(count / 2)
+            .map_or(line.text.len(), |(i, _)| i);
+        let (first, second) = line.text.split_at(middle);
+        let set = |items| ItemSet::from_items(items, scheme).map_err(|e| line.error(e.to_string()));
+        let compartments = [set(first)?, set(second)?];
+        let common_item = compartments[0]
+            .intersection(compartments[1])
+            .single()
+            .map_err(|e| line.error(format!("between compartments, {}", e)))?;
+
+        Ok(Rucksack {
+            compartments,
+            common_item,
+        })
     }
 
-    fn group_item_value(&self) -> usize {
-        priority::get_priority(self.group_common.expect("No value for common group value"))
+    pub fn compartments(&self) -> &[ItemSet<'a>; 2] {
+        &self.compartments
     }
 
-    fn add_group_value(&mut self, c: char) {
-        self.group_common = Some(c);
+    /// Every item in the rucksack.
+    pub fn items(&self) -> ItemSet<'a> {
+        self.compartments[0].union(self.compartments[1])
     }
-}
 
-fn get_common_char(str_vec: Vec<&str>) -> Option<char> {
-    'char_loop: for c in str_vec[0].chars() {
-        for (index, s) in str_vec[1..].iter().enumerate() {
-            if !s.contains(c) {
-                continue 'char_loop;
-            } else if index == str_vec.len() - 2 {
-                return Some(c);
-            }
-        }
+    /// The item in both compartments.
+    pub fn common_item(&self) -> char {
+        self.common_item
     }
 
-    None
+    fn common_item_value(&self) -> usize {
+        self.compartments[0]
+            .scheme()
+            .priority(self.common_item)
+            .expect("Common item is in the scheme")
+    }
 }
 
 //
@@ -96,44 +110,86 @@ fn get_common_char(str_vec: Vec<&str>) -> Option<char> {
 //
 #[cfg(test)]
 mod tests {
-    use super::priority::*;
+    use super::priority::Alphabet;
     use super::*;
 
     #[test]
     fn test_sum_of_rucksack_commons_items_from_file() {
-        let rs = get_rucksacks_from_file("resources/test/03_rucksack.txt");
+        let rs = get_rucksacks_from_file("resources/test/03_rucksack.txt").unwrap();
 
         let ans = get_sum_of_priorities_for_common_items(&rs);
         assert_eq!(157, ans);
 
-        let ans = get_sum_of_priorities_for_group(&rs);
-        assert_eq!(70, ans);
-    }
-
-    #[test]
-    fn test_priorities_value() {
-        let priority = get_priority('c');
-        assert_eq!(3, priority);
+        let ans = get_sum_of_priorities_for_group(&rs, Grouping::Consecutive(3));
+        assert_eq!(Ok(70), ans);
     }
 
     #[test]
     fn test_rucksack_parse() {
         let s = "PmmdzqPrVvPwwTWBwg";
-        let rs = Rucksack::from_string(s);
+        let rs = Rucksack::from_line(&Line { number: 1, text: s }, &AocScheme).unwrap();
 
         assert_eq!('P', rs.common_item);
         assert_eq!(42, rs.common_item_value());
+        assert_eq!(7, rs.compartments()[0].len());
+        assert_eq!(13, rs.items().len());
     }
 
     #[test]
-    fn test_common_char() {
-        let v = vec!["WgXc", "YYcs", "WcY", "hHcH"];
-        assert_eq!('c', get_common_char(v).unwrap());
+    fn test_rucksack_parse_errors() {
+        let error = |text| {
+            Rucksack::from_line(&Line { number: 1, text }, &AocScheme)
+                .unwrap_err()
+                .message
+        };
+
+        assert_eq!(
+            "5 items can't be split evenly between two compartments",
+            error("abcab")
+        );
+        assert_eq!(
+            "between compartments, no item is common to all",
+            error("abcd")
+        );
+        assert_eq!(
+            "between compartments, several items are common to all: a, b",
+            error("abab")
+        );
+        assert_eq!("'1' is not an item", error("a1ab"));
+        assert_eq!(
+            "3 items can't be split evenly between two compartments",
+            error("aéb")
+        );
+        assert_eq!("'é' is not an item", error("aébc"));
+    }
+
+    #[test]
+    fn test_rucksacks_with_other_scheme() {
+        let digits = Alphabet::digits();
+        let input = Input::new("rucksacks.txt", "1213\n4556\n");
 
-        let v = vec!["WgXc"];
-        assert_eq!(None, get_common_char(v));
+        let rucksacks = parse_rucksacks(&input, &digits).unwrap();
+        assert_eq!('1', rucksacks[0].common_item());
+        assert_eq!(2 + 6, get_sum_of_priorities_for_common_items(&rucksacks));
 
-        let v = vec!["WgXc", "YYcs"];
-        assert_eq!('c', get_common_char(v).unwrap());
+        let error = parse_rucksacks(&Input::new("rucksacks.txt", "1213\n4a56\n"), &digits);
+        assert_eq!(2, error.unwrap_err().line);
+        assert!(parse_rucksacks(&input, &AocScheme).is_err());
+    }
+
+    #[test]
+    fn test_rucksacks_with_multi_byte_items() {
+        let greek = Alphabet::new("αβγδ").unwrap();
+        let input = Input::new("rucksacks.txt", "αβγβ\nαβγ\n");
+
+        let rucksack = Rucksack::from_line(&input.lines().next().unwrap(), &greek).unwrap();
+        assert_eq!('β', rucksack.common_item());
+
+        let error = parse_rucksacks(&input, &greek).unwrap_err();
+        assert_eq!(2, error.line);
+        assert_eq!(
+            "3 items can't be split evenly between two compartments",
+            error.message
+        );
     }
 }
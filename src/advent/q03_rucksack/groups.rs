@@ -0,0 +1,291 @@
+use std::fmt;
+
+use super::items::{ItemError, ItemSet};
+use super::Rucksack;
+
+/// How rucksacks are put into groups of elves.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// Each run of `size` rucksacks, in input order.
+    Consecutive(usize),
+    /// Groups of `size` rucksacks with exactly one item in common. Each rucksack not yet grouped, in input
+    /// order, is grouped with the first later rucksacks that make such a group.
+    SharedItem(usize),
+}
+
+/// Rucksacks by their position in the input, starting at 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Groups<'a> {
+    pub groups: Vec<Group<'a>>,
+    /// Rucksacks not in a full group: a partial group at the end, or rucksacks no group was found for.
+    pub ungrouped: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group<'a> {
+    pub members: Vec<usize>,
+    /// Items in every member's rucksack.
+    pub common: ItemSet<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupError {
+    Ungrouped(Vec<usize>),
+    Badge {
+        members: Vec<usize>,
+        error: ItemError,
+    },
+}
+
+pub fn group<'a>(rucksacks: &[Rucksack<'a>], grouping: Grouping) -> Groups<'a> {
+    let sets: Vec<ItemSet<'a>> = rucksacks.iter().map(|rs| rs.items()).collect();
+
+    match grouping {
+        Grouping::Consecutive(size) => consecutive(&sets, size),
+        Grouping::SharedItem(size) => shared_item(&sets, size),
+    }
+}
+
+#[allow(dead_code)]
+impl Groups<'_> {
+    /// True if every rucksack is in a full group.
+    pub fn is_complete(&self) -> bool {
+        self.ungrouped.is_empty()
+    }
+
+    /// The badge of every group, in group order. Ungrouped rucksacks, or a group without exactly one item
+    /// in common, are an error.
+    pub fn badges(&self) -> Result<Vec<char>, GroupError> {
+        if !self.is_complete() {
+            return Err(GroupError::Ungrouped(self.ungrouped.clone()));
+        }
+
+        self.groups
+            .iter()
+            .map(|g| {
+                g.badge().map_err(|error| GroupError::Badge {
+                    members: g.members.clone(),
+                    error,
+                })
+            })
+            .collect()
+    }
+
+    pub fn badge_priority_sum(&self) -> Result<usize, GroupError> {
+        let badges = self.badges()?;
+
+        Ok(self
+            .groups
+            .iter()
+            .zip(badges)
+            .map(|(g, badge)| {
+                g.common
+                    .scheme()
+                    .priority(badge)
+                    .expect("Badge is an item of the scheme")
+            })
+            .sum())
+    }
+}
+
+impl Group<'_> {
+    /// The one item every member carries.
+    pub fn badge(&self) -> Result<char, ItemError> {
+        self.common.single()
+    }
+}
+
+fn consecutive<'a>(sets: &[ItemSet<'a>], size: usize) -> Groups<'a> {
+    if size == 0 {
+        return Groups {
+            groups: Vec::new(),
+            ungrouped: (0..sets.len()).collect(),
+        };
+    }
+
+    let full = sets.len() - sets.len() % size;
+    let groups = (0..full)
+        .step_by(size)
+        .map(|start| {
+            let members: Vec<usize> = (start..start + size).collect();
+            Group {
+                common: common(sets, &members),
+                members,
+            }
+        })
+        .collect();
+
+    Groups {
+        groups,
+        ungrouped: (full..sets.len()).collect(),
+    }
+}
+
+fn shared_item<'a>(sets: &[ItemSet<'a>], size: usize) -> Groups<'a> {
+    let mut free = vec![true; sets.len()];
+    let mut groups = Vec::new();
+    let mut ungrouped = Vec::new();
+
+    for first in 0..sets.len() {
+        if !free[first] {
+            continue;
+        }
+
+        let mut members = vec![first];
+        if size > 0 && extend(sets, &free, &mut members, sets[first], size) {
+            members.iter().for_each(|m| free[*m] = false);
+            groups.push(Group {
+                common: common(sets, &members),
+                members,
+            });
+        } else {
+            free[first] = false;
+            ungrouped.push(first);
+        }
+    }
+
+    Groups { groups, ungrouped }
+}
+
+// depth-first search for later free rucksacks to add to `members`, keeping only those that still share
+// an item, until there are `size` members with exactly one item in common
+fn extend(
+    sets: &[ItemSet],
+    free: &[bool],
+    members: &mut Vec<usize>,
+    shared: ItemSet,
+    size: usize,
+) -> bool {
+    if members.len() == size {
+        return shared.len() == 1;
+    }
+
+    let last = *members.last().expect("Group has a first member");
+    for next in (last + 1)..sets.len() {
+        let shared = shared.intersection(sets[next]);
+        if !free[next] || shared.is_empty() {
+            continue;
+        }
+
+        members.push(next);
+        if extend(sets, free, members, shared, size) {
+            return true;
+        }
+        members.pop();
+    }
+
+    false
+}
+
+fn common<'a>(sets: &[ItemSet<'a>], members: &[usize]) -> ItemSet<'a> {
+    members
+        .iter()
+        .skip(1)
+        .fold(sets[members[0]], |acc, m| acc.intersection(sets[*m]))
+}
+
+impl fmt::Display for GroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // rucksacks are numbered from 1 in messages
+        let numbers = |members: &[usize]| {
+            members
+                .iter()
+                .map(|m| (m + 1).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            GroupError::Ungrouped(members) => {
+                write!(f, "rucksacks {} are not in a full group", numbers(members))
+            }
+            GroupError::Badge { members, error } => {
+                write!(
+                    f,
+                    "in the group of rucksacks {}, {}",
+                    numbers(members),
+                    error
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GroupError {}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::q03_rucksack::priority::AocScheme;
+    use crate::common::str::Line;
+
+    fn rucksacks(lines: &[&str]) -> Vec<Rucksack<'static>> {
+        lines
+            .iter()
+            .map(|text| Rucksack::from_line(&Line { number: 1, text }, &AocScheme).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_consecutive() {
+        let rs = rucksacks(&["abac", "abad", "aeaf", "ghgi", "gjgk"]);
+
+        let groups = group(&rs, Grouping::Consecutive(3));
+        assert_eq!(1, groups.groups.len());
+        assert_eq!(vec![0, 1, 2], groups.groups[0].members);
+        assert_eq!(Ok('a'), groups.groups[0].badge());
+        assert_eq!(vec![3, 4], groups.ungrouped);
+        assert_eq!(Err(GroupError::Ungrouped(vec![3, 4])), groups.badges());
+
+        let groups = group(&rs, Grouping::Consecutive(5));
+        assert_eq!(vec![0, 1, 2, 3, 4], groups.groups[0].members);
+        assert!(groups.is_complete());
+        assert_eq!(
+            "in the group of rucksacks 1, 2, 3, 4, 5, no item is common to all",
+            groups.badges().unwrap_err().to_string()
+        );
+
+        let groups = group(&rs[..4], Grouping::Consecutive(2));
+        assert_eq!(
+            Err(GroupError::Badge {
+                members: vec![0, 1],
+                error: ItemError::SeveralCommonItems(vec!['a', 'b'])
+            }),
+            groups.badges()
+        );
+
+        assert_eq!(
+            Ok(1),
+            group(&rs[..3], Grouping::Consecutive(3)).badge_priority_sum()
+        );
+        assert_eq!(5, group(&rs, Grouping::Consecutive(0)).ungrouped.len());
+    }
+
+    #[test]
+    fn test_shared_item() {
+        // rucksacks 0 and 1 share two items, so 0 is grouped with 2 instead
+        let rs = rucksacks(&["abac", "abad", "aeaf", "ghgi", "gjgk", "lmln"]);
+
+        let groups = group(&rs, Grouping::SharedItem(2));
+        let members: Vec<&Vec<usize>> = groups.groups.iter().map(|g| &g.members).collect();
+        assert_eq!(vec![&vec![0, 2], &vec![3, 4]], members);
+        assert_eq!(vec![1, 5], groups.ungrouped);
+
+        let groups = group(&rs, Grouping::SharedItem(3));
+        assert_eq!(vec![0, 1, 2], groups.groups[0].members);
+        assert_eq!(vec![3, 4, 5], groups.ungrouped);
+    }
+
+    #[test]
+    fn test_shared_item_matches_puzzle_groups() {
+        let rs = super::super::get_rucksacks_from_file("resources/test/03_rucksack.txt").unwrap();
+
+        let groups = group(&rs, Grouping::SharedItem(3));
+        assert!(groups.is_complete());
+        assert_eq!(Ok(70), groups.badge_priority_sum());
+    }
+}
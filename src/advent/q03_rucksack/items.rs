@@ -0,0 +1,253 @@
+use std::fmt;
+
+use super::priority::{PriorityScheme, MAX_ITEMS};
+
+/// A set of items, one bit per item priority. A scheme has at most 64 items, so a set fits in a `u64`, and
+/// intersection and union are single operations. Sets combined with each other should use the same scheme.
+#[derive(Debug, Clone, Copy)]
+pub struct ItemSet<'a> {
+    bits: u64,
+    scheme: &'a dyn PriorityScheme,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ItemError {
+    UnknownItem(char),
+    /// An item whose priority, from a scheme that breaks the `PriorityScheme` contract, isn't from 1 to
+    /// `MAX_ITEMS`.
+    BadPriority(char, usize),
+    NoCommonItem,
+    SeveralCommonItems(Vec<char>),
+}
+
+#[allow(dead_code)]
+impl<'a> ItemSet<'a> {
+    pub fn new(scheme: &'a dyn PriorityScheme) -> Self {
+        ItemSet { bits: 0, scheme }
+    }
+
+    /// Every item in the scheme.
+    pub fn all(scheme: &'a dyn PriorityScheme) -> Self {
+        let bits = match scheme.item_count() {
+            MAX_ITEMS.. => u64::MAX,
+            count => (1 << count) - 1,
+        };
+
+        ItemSet { bits, scheme }
+    }
+
+    /// The set of items in `items`. Repeated items are only counted once.
+    pub fn from_items(items: &str, scheme: &'a dyn PriorityScheme) -> Result<Self, ItemError> {
+        let mut set = ItemSet::new(scheme);
+        for item in items.chars() {
+            set.insert(item)?;
+        }
+
+        Ok(set)
+    }
+
+    pub fn scheme(&self) -> &'a dyn PriorityScheme {
+        self.scheme
+    }
+
+    /// Add `item`. Returns true if it was not already in the set.
+    pub fn insert(&mut self, item: char) -> Result<bool, ItemError> {
+        let bit = self.bit(item)?;
+        let added = self.bits & bit == 0;
+        self.bits |= bit;
+
+        Ok(added)
+    }
+
+    pub fn contains(&self, item: char) -> bool {
+        self.bit(item).is_ok_and(|bit| self.bits & bit != 0)
+    }
+
+    pub fn intersection(&self, other: ItemSet) -> ItemSet<'a> {
+        ItemSet {
+            bits: self.bits & other.bits,
+            scheme: self.scheme,
+        }
+    }
+
+    pub fn union(&self, other: ItemSet) -> ItemSet<'a> {
+        ItemSet {
+            bits: self.bits | other.bits,
+            scheme: self.scheme,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Priorities of the items in the set, lowest first.
+    pub fn priorities(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..MAX_ITEMS)
+            .filter(|bit| self.bits & (1 << bit) != 0)
+            .map(|bit| bit + 1)
+    }
+
+    /// Items in the set, in priority order.
+    pub fn items(&self) -> impl Iterator<Item = char> + '_ {
+        self.priorities()
+            .map(|p| self.scheme.item(p).expect("Every bit is an item"))
+    }
+
+    /// The only item in the set. An empty set, or one with several items, is an error.
+    pub fn single(&self) -> Result<char, ItemError> {
+        match self.len() {
+            0 => Err(ItemError::NoCommonItem),
+            1 => Ok(self.items().next().expect("Set has one item")),
+            _ => Err(ItemError::SeveralCommonItems(self.items().collect())),
+        }
+    }
+
+    /// Sum of the priorities of every item in the set.
+    pub fn priority_sum(&self) -> usize {
+        self.priorities().sum()
+    }
+
+    fn bit(&self, item: char) -> Result<u64, ItemError> {
+        match self.scheme.priority(item) {
+            None => Err(ItemError::UnknownItem(item)),
+            Some(p @ 1..=MAX_ITEMS) => Ok(1 << (p - 1)),
+            Some(p) => Err(ItemError::BadPriority(item, p)),
+        }
+    }
+}
+
+impl PartialEq for ItemSet<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl Eq for ItemSet<'_> {}
+
+impl fmt::Display for ItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemError::UnknownItem(c) => write!(f, "'{}' is not an item", c),
+            ItemError::BadPriority(c, p) => {
+                write!(f, "'{}' has priority {}, outside 1 to {}", c, p, MAX_ITEMS)
+            }
+            ItemError::NoCommonItem => write!(f, "no item is common to all"),
+            ItemError::SeveralCommonItems(items) => {
+                let items: Vec<String> = items.iter().map(|c| c.to_string()).collect();
+                write!(f, "several items are common to all: {}", items.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ItemError {}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::q03_rucksack::priority::{Alphabet, AocScheme};
+
+    #[test]
+    fn test_item_set() {
+        let mut set = ItemSet::from_items("WgXcc", &AocScheme).unwrap();
+        assert_eq!(4, set.len());
+        assert!(set.contains('W'));
+        assert!(!set.contains('w'));
+        assert!(!set.contains('1'));
+
+        assert_eq!(Ok(true), set.insert('a'));
+        assert_eq!(Ok(false), set.insert('a'));
+        assert_eq!(Err(ItemError::UnknownItem('_')), set.insert('_'));
+        assert_eq!(
+            vec!['a', 'c', 'g', 'W', 'X'],
+            set.items().collect::<Vec<_>>()
+        );
+
+        assert_eq!(52, ItemSet::all(&AocScheme).len());
+        assert!(ItemSet::new(&AocScheme).is_empty());
+    }
+
+    #[test]
+    fn test_intersection_and_union() {
+        let sets: Vec<ItemSet> = ["WgXc", "YYcs", "WcY", "hHcH"]
+            .iter()
+            .map(|s| ItemSet::from_items(s, &AocScheme).unwrap())
+            .collect();
+
+        let common = sets
+            .iter()
+            .fold(ItemSet::all(&AocScheme), |acc, s| acc.intersection(*s));
+        assert_eq!(Ok('c'), common.single());
+
+        let common = sets[0].intersection(sets[2]);
+        assert_eq!(
+            Err(ItemError::SeveralCommonItems(vec!['c', 'W'])),
+            common.single()
+        );
+        assert_eq!(3 + 49, common.priority_sum());
+
+        assert_eq!(
+            Err(ItemError::NoCommonItem),
+            ItemSet::new(&AocScheme).single()
+        );
+        assert_eq!(6, sets[0].union(sets[1]).len());
+    }
+
+    #[test]
+    fn test_other_schemes() {
+        let digits = Alphabet::digits();
+        let set = ItemSet::from_items("7031", &digits).unwrap();
+        assert_eq!(vec!['0', '1', '3', '7'], set.items().collect::<Vec<_>>());
+        assert_eq!(1 + 2 + 4 + 8, set.priority_sum());
+        assert_eq!(
+            Err(ItemError::UnknownItem('a')),
+            ItemSet::from_items("70a1", &digits)
+        );
+
+        let long: String = ('\u{100}'..'\u{140}').collect();
+        let long = Alphabet::new(&long).unwrap();
+        let all = ItemSet::all(&long);
+        assert_eq!(64, all.len());
+        assert!(all.contains('\u{13f}'));
+    }
+
+    // gives every item the priority of its code, however large
+    #[derive(Debug)]
+    struct Unchecked;
+
+    impl PriorityScheme for Unchecked {
+        fn item_count(&self) -> usize {
+            MAX_ITEMS
+        }
+
+        fn priority(&self, item: char) -> Option<usize> {
+            Some(item as usize)
+        }
+
+        fn item(&self, priority: usize) -> Option<char> {
+            char::from_u32(priority as u32)
+        }
+    }
+
+    #[test]
+    fn test_priorities_outside_the_limit() {
+        let mut set = ItemSet::new(&Unchecked);
+        assert_eq!(Ok(true), set.insert('\u{1}'));
+        assert_eq!(Ok(true), set.insert('@'));
+        assert_eq!(Err(ItemError::BadPriority('\0', 0)), set.insert('\0'));
+        assert_eq!(Err(ItemError::BadPriority('A', 65)), set.insert('A'));
+        assert!(!set.contains('A'));
+        assert_eq!(
+            "'A' has priority 65, outside 1 to 64",
+            ItemError::BadPriority('A', 65).to_string()
+        );
+    }
+}
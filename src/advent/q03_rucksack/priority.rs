@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Most items a scheme can have, so that a set of them fits in an `ItemSet`.
+pub const MAX_ITEMS: usize = 64;
+
+/// The items that can be packed, each with a priority. Priorities run from 1 to `item_count`, which is at
+/// most `MAX_ITEMS`, and every item has a different one. An `ItemSet` rejects items whose priority is
+/// outside 1 to `MAX_ITEMS`.
+pub trait PriorityScheme: fmt::Debug {
+    fn item_count(&self) -> usize;
+
+    fn priority(&self, item: char) -> Option<usize>;
+
+    /// The item with the given priority.
+    fn item(&self, priority: usize) -> Option<char>;
+}
+
+/// The puzzle's scheme: a-z are 1 to 26, and A-Z are 27 to 52.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AocScheme;
+
+/// A scheme of any items, given in priority order.
+#[derive(Debug, Clone)]
+pub struct Alphabet {
+    items: Vec<char>,
+    priorities: HashMap<char, usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AlphabetError {
+    Empty,
+    TooManyItems(usize),
+    DuplicateItem(char),
+}
+
+impl PriorityScheme for AocScheme {
+    fn item_count(&self) -> usize {
+        52
+    }
+
+    fn priority(&self, item: char) -> Option<usize> {
+        match item {
+            'a'..='z' => Some(item as usize - 'a' as usize + 1),
+            'A'..='Z' => Some(item as usize - 'A' as usize + 27),
+            _ => None,
+        }
+    }
+
+    fn item(&self, priority: usize) -> Option<char> {
+        match priority {
+            1..=26 => char::from_u32('a' as u32 + priority as u32 - 1),
+            27..=52 => char::from_u32('A' as u32 + priority as u32 - 27),
+            _ => None,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Alphabet {
+    /// The characters of `items`, with the first having priority 1.
+    pub fn new(items: &str) -> Result<Self, AlphabetError> {
+        let items: Vec<char> = items.chars().collect();
+        if items.is_empty() {
+            return Err(AlphabetError::Empty);
+        }
+        if items.len() > MAX_ITEMS {
+            return Err(AlphabetError::TooManyItems(items.len()));
+        }
+
+        let mut priorities = HashMap::with_capacity(items.len());
+        for (i, item) in items.iter().enumerate() {
+            if priorities.insert(*item, i + 1).is_some() {
+                return Err(AlphabetError::DuplicateItem(*item));
+            }
+        }
+
+        Ok(Alphabet { items, priorities })
+    }
+
+    /// 0-9, with priorities 1 to 10.
+    pub fn digits() -> Self {
+        Alphabet::new("0123456789").expect("Digits are a valid alphabet")
+    }
+}
+
+impl PriorityScheme for Alphabet {
+    fn item_count(&self) -> usize {
+        self.items.len()
+    }
+
+    fn priority(&self, item: char) -> Option<usize> {
+        self.priorities.get(&item).copied()
+    }
+
+    fn item(&self, priority: usize) -> Option<char> {
+        self.items.get(priority.checked_sub(1)?).copied()
+    }
+}
+
+impl fmt::Display for AlphabetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlphabetError::Empty => write!(f, "an alphabet needs at least one item"),
+            AlphabetError::TooManyItems(n) => {
+                write!(f, "{} items is more than the {} allowed", n, MAX_ITEMS)
+            }
+            AlphabetError::DuplicateItem(c) => write!(f, "item '{}' is in the alphabet twice", c),
+        }
+    }
+}
+
+impl std::error::Error for AlphabetError {}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aoc_scheme() {
+        assert_eq!(Some(3), AocScheme.priority('c'));
+        assert_eq!(Some(27), AocScheme.priority('A'));
+        assert_eq!(None, AocScheme.priority('_'));
+        assert_eq!(None, AocScheme.priority('é'));
+        assert_eq!(Some('Z'), AocScheme.item(52));
+        assert_eq!(None, AocScheme.item(0));
+        assert_eq!(None, AocScheme.item(53));
+
+        for priority in 1..=AocScheme.item_count() {
+            let item = AocScheme.item(priority).unwrap();
+            assert_eq!(Some(priority), AocScheme.priority(item));
+        }
+    }
+
+    #[test]
+    fn test_alphabet() {
+        let digits = Alphabet::digits();
+        assert_eq!(10, digits.item_count());
+        assert_eq!(Some(1), digits.priority('0'));
+        assert_eq!(None, digits.priority('a'));
+        assert_eq!(Some('9'), digits.item(10));
+
+        let greek = Alphabet::new("αβγδ").unwrap();
+        assert_eq!(Some(3), greek.priority('γ'));
+        assert_eq!(Some('δ'), greek.item(4));
+        assert_eq!(None, greek.item(0));
+
+        assert_eq!(AlphabetError::Empty, Alphabet::new("").unwrap_err());
+        assert_eq!(
+            AlphabetError::DuplicateItem('b'),
+            Alphabet::new("abcb").unwrap_err()
+        );
+        let long: String = ('\u{100}'..'\u{141}').collect();
+        assert_eq!(
+            AlphabetError::TooManyItems(65),
+            Alphabet::new(&long).unwrap_err()
+        );
+    }
+}
@@ -1,59 +1,207 @@
-use crate::common::io;
+use std::collections::BTreeSet;
 
-pub fn get_cleaning_pairs(filename: &str) -> Vec<CleaningPair> {
-    let lines = io::read_file_as_vector(filename).expect("Could not read file");
+use crate::common::interval::{Interval, RangeSet};
+use crate::common::io::Input;
+use crate::common::str::{Line, ParseError};
 
-    lines
-        .iter()
-        .map(|l| parse_line_into_pair(l))
-        .collect::<Vec<CleaningPair>>()
+pub fn get_cleaning_pairs(filename: &str) -> Result<Vec<CleaningPair>, std::io::Error> {
+    let input = Input::read(filename)?;
+    let pairs = input
+        .lines()
+        .map(|l| parse_line_into_pair(&l))
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.in_file(input.name()))?;
+
+    Ok(pairs)
 }
 
-pub fn get_count_of_fully_overlapping_pairs(pairs: &[CleaningPair]) -> u16 {
-    pairs.iter().fold(0, |total, pair| {
-        total + (if pair.do_pairs_fully_overlap() { 1 } else { 0 })
-    })
+pub fn get_count_of_fully_overlapping_pairs(pairs: &[CleaningPair]) -> usize {
+    pairs.iter().filter(|p| p.do_pairs_fully_overlap()).count()
+}
+
+pub fn get_count_of_all_overlapping_pairs(pairs: &[CleaningPair]) -> usize {
+    pairs.iter().filter(|p| p.do_pairs_overlap()).count()
+}
+
+/// Sections cleaned by anyone, across every pair. Sections are numbered from 1, so sections before the
+/// first one cleaned are counted as nobody's.
+#[allow(dead_code)]
+pub fn get_coverage_report(pairs: &[CleaningPair]) -> CoverageReport {
+    let covered = RangeSet::from_intervals(elves(pairs).map(|(_, sections)| sections));
+    let uncleaned = match covered.span() {
+        Some(span) => {
+            let bounds = Interval::new(span.start().min(1), span.end()).expect("Span is ordered");
+            covered.gaps_within(bounds)
+        }
+        None => Vec::new(),
+    };
+
+    CoverageReport {
+        elves: pairs.len() * 2,
+        total_covered: covered.coverage(),
+        covered,
+        uncleaned,
+    }
+}
+
+/// Sections cleaned by more than `k` elves, found by sweeping over the starts and ends of every elf's
+/// sections in order.
+#[allow(dead_code)]
+pub fn get_crowding_report(pairs: &[CleaningPair], k: usize) -> CrowdingReport {
+    // +1 where an elf's sections start, and -1 just after they end
+    let mut events: Vec<(u32, i64)> = elves(pairs)
+        .flat_map(|(_, sections)| {
+            let end = sections.end().checked_add(1).map(|after| (after, -1));
+            [Some((sections.start(), 1)), end]
+        })
+        .flatten()
+        .collect();
+    events.sort_unstable();
+
+    let mut sections = RangeSet::new();
+    let mut elves = 0;
+    let mut most_elves = 0;
+    let mut i = 0;
+    while i < events.len() {
+        let position = events[i].0;
+        while i < events.len() && events[i].0 == position {
+            elves += events[i].1;
+            i += 1;
+        }
+
+        let count = elves as usize;
+        most_elves = most_elves.max(count);
+        if count > k {
+            // only an elf cleaning up to the last section has no event after it
+            let end = events.get(i).map_or(u32::MAX, |(next, _)| next - 1);
+            sections.insert(Interval::new(position, end).expect("Events are sorted"));
+        }
+    }
+
+    CrowdingReport {
+        k,
+        total: sections.coverage(),
+        sections,
+        most_elves,
+    }
 }
 
-pub fn get_count_of_all_overlapping_pairs(pairs: &[CleaningPair]) -> u16 {
-    pairs.iter().fold(0, |total, pair| {
-        total + (if pair.do_pairs_overlap() { 1 } else { 0 })
+/// Every two elves, from any pairs, whose sections overlap. Elves are swept in order of their first
+/// section, keeping the elves whose sections haven't ended yet, so only elves that do overlap are compared.
+#[allow(dead_code)]
+pub fn get_overlap_report(pairs: &[CleaningPair]) -> OverlapReport {
+    let mut elves: Vec<(ElfId, Interval<u32>)> = elves(pairs).collect();
+    elves.sort_unstable_by_key(|(id, sections)| (sections.start(), *id));
+
+    // (end, elf) for every elf whose sections might still overlap a later elf's
+    let mut active: BTreeSet<(u32, ElfId)> = BTreeSet::new();
+    let mut overlaps = Vec::new();
+    for (id, sections) in elves {
+        while let Some(first) = active.first().copied() {
+            if first.0 >= sections.start() {
+                break;
+            }
+            active.remove(&first);
+        }
+
+        overlaps.extend(
+            active
+                .iter()
+                .map(|(_, other)| (*other.min(&id), *other.max(&id))),
+        );
+        active.insert((sections.end(), id));
+    }
+    overlaps.sort_unstable();
+
+    OverlapReport { overlaps }
+}
+
+/// Every elf with their sections, in input order.
+fn elves(pairs: &[CleaningPair]) -> impl Iterator<Item = (ElfId, Interval<u32>)> + '_ {
+    pairs.iter().enumerate().flat_map(|(pair, p)| {
+        [
+            (ElfId { pair, elf: 0 }, p.elf1),
+            (ElfId { pair, elf: 1 }, p.elf2),
+        ]
     })
 }
 
-#[derive(Debug)]
-pub struct CleaningPair {
-    elf1: (u8, u8),
-    elf2: (u8, u8),
+/// An elf, by the position of their pair in the input and whether they come first (0) or second (1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ElfId {
+    pub pair: usize,
+    pub elf: usize,
 }
 
-impl CleaningPair {
-    fn do_pairs_fully_overlap(&self) -> bool {
-        let p1_in_p2 = |p1: (u8, u8), p2: (u8, u8)| -> bool { (p1.0 >= p2.0) && (p1.1 <= p2.1) };
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageReport {
+    pub elves: usize,
+    pub covered: RangeSet<u32>,
+    pub total_covered: u64,
+    /// Sections from 1 to the last one cleaned that nobody cleans.
+    pub uncleaned: Vec<Interval<u32>>,
+}
 
-        p1_in_p2(self.elf1, self.elf2) || p1_in_p2(self.elf2, self.elf1)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrowdingReport {
+    pub k: usize,
+    /// Sections cleaned by more than `k` elves.
+    pub sections: RangeSet<u32>,
+    pub total: u64,
+    /// The most elves cleaning any one section.
+    pub most_elves: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlapReport {
+    /// Elves whose sections overlap, each with the lower `ElfId` first, in order.
+    pub overlaps: Vec<(ElfId, ElfId)>,
+}
+
+#[allow(dead_code)]
+impl OverlapReport {
+    pub fn len(&self) -> usize {
+        self.overlaps.len()
     }
 
-    fn do_pairs_overlap(&self) -> bool {
-        // Pairs don't overlap if the start of one is greater than the end of the other.
-        !((self.elf1.0 > self.elf2.1) || (self.elf2.0 > self.elf1.1))
+    pub fn is_empty(&self) -> bool {
+        self.overlaps.is_empty()
+    }
+
+    /// Overlaps between elves of different pairs, which the per-pair counts never see.
+    pub fn across_pairs(&self) -> impl Iterator<Item = &(ElfId, ElfId)> {
+        self.overlaps.iter().filter(|(a, b)| a.pair != b.pair)
     }
 }
 
-fn parse_line_into_pair(line: &str) -> CleaningPair {
-    let (first, second) = line.split_once(',').expect("Cannot split string on ','");
+/// Section IDs are given as `u32`, so an elf can be assigned well past section 255.
+#[derive(Debug)]
+pub struct CleaningPair {
+    elf1: Interval<u32>,
+    elf2: Interval<u32>,
+}
 
-    CleaningPair {
-        elf1: string_to_range_ints(first),
-        elf2: string_to_range_ints(second),
+impl CleaningPair {
+    fn do_pairs_fully_overlap(&self) -> bool {
+        self.elf1.contains_interval(&self.elf2) || self.elf2.contains_interval(&self.elf1)
+    }
+
+    fn do_pairs_overlap(&self) -> bool {
+        self.elf1.overlaps(&self.elf2)
     }
 }
 
-fn string_to_range_ints(s: &str) -> (u8, u8) {
-    let (start, end) = s.split_once('-').expect("Cannot split string on '-'");
-    let parse = |n: &str| -> u8 { n.trim().parse().expect("Cannot parse as int") };
+fn parse_line_into_pair(line: &Line) -> Result<CleaningPair, ParseError> {
+    let (start1, end1, start2, end2) = line.scan("{}-{},{}-{}")?;
+    let interval = |start, end| {
+        Interval::new(start, end)
+            .ok_or_else(|| line.error(format!("sections {}-{} end before they start", start, end)))
+    };
 
-    (parse(start), parse(end))
+    Ok(CleaningPair {
+        elf1: interval(start1, end1)?,
+        elf2: interval(start2, end2)?,
+    })
 }
 
 //
@@ -64,56 +212,139 @@ fn string_to_range_ints(s: &str) -> (u8, u8) {
 mod tests {
     use super::*;
 
+    fn line(text: &str) -> Line<'_> {
+        Line { number: 1, text }
+    }
+
+    fn interval(start: u32, end: u32) -> Interval<u32> {
+        Interval::new(start, end).unwrap()
+    }
+
+    fn cleaning_pair(elf1: (u32, u32), elf2: (u32, u32)) -> CleaningPair {
+        CleaningPair { elf1: interval(elf1.0, elf1.1), elf2: interval(elf2.0, elf2.1) }
+    }
+
     #[test]
     fn test_parse_line_into_pair() {
-        let line = "2-4,6-8";
-        let pair = parse_line_into_pair(line);
+        let pair = parse_line_into_pair(&line("2-4,6-8")).unwrap();
+
+        assert_eq!(interval(2, 4), pair.elf1);
+        assert_eq!(interval(6, 8), pair.elf2);
+
+        let pair = parse_line_into_pair(&line("255-300,1000-70000")).unwrap();
+        assert_eq!(interval(255, 300), pair.elf1);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |text| parse_line_into_pair(&line(text)).unwrap_err().message;
 
-        assert_eq!((2, 4), pair.elf1);
-        assert_eq!((6, 8), pair.elf2);
+        assert_eq!("sections 4-2 end before they start", error("4-2,6-8"));
+        assert!(error("2-4").starts_with("expected"));
+        assert!(error("2-4,6").starts_with("expected"));
+        assert!(error("2-4,-6-8").contains("cannot parse"));
     }
 
     #[test]
     fn test_do_pairs_fully_overlap() {
-        let pair = CleaningPair { elf1: (1, 4), elf2: (2, 3) };
+        let pair = cleaning_pair((1, 4), (2, 3));
         assert!(pair.do_pairs_fully_overlap());
 
-        let pair = CleaningPair { elf1: (2, 4), elf2: (2, 4) };
+        let pair = cleaning_pair((2, 4), (2, 4));
         assert!(pair.do_pairs_fully_overlap());
 
-        let pair = CleaningPair { elf1: (3, 3), elf2: (2, 3) };
+        let pair = cleaning_pair((3, 3), (2, 3));
         assert!(pair.do_pairs_fully_overlap());
 
-        let pair = CleaningPair { elf1: (2, 3), elf2: (1, 4) };
+        let pair = cleaning_pair((2, 3), (1, 4));
         assert!(pair.do_pairs_fully_overlap());
     }
 
     #[test]
     fn test_do_pairs_overlap_at_all() {
-        let pair = CleaningPair { elf1: (1, 4), elf2: (2, 3) };
+        let pair = cleaning_pair((1, 4), (2, 3));
         assert!(pair.do_pairs_overlap());
 
-        let pair = CleaningPair { elf1: (2, 4), elf2: (3, 5) };
+        let pair = cleaning_pair((2, 4), (3, 5));
         assert!(pair.do_pairs_overlap());
 
-        let pair = CleaningPair { elf1: (3, 3), elf2: (1, 3) };
+        let pair = cleaning_pair((3, 3), (1, 3));
         assert!(pair.do_pairs_overlap());
 
-        let pair = CleaningPair { elf1: (2, 5), elf2: (1, 3) };
+        let pair = cleaning_pair((2, 5), (1, 3));
         assert!(pair.do_pairs_overlap());
     }
 
     #[test]
     fn test_get_count_of_fully_overlapping_pairs() {
-        let pairs = get_cleaning_pairs("resources/test/04_cleaning_pairs.txt");
+        let pairs = get_cleaning_pairs("resources/test/04_cleaning_pairs.txt").unwrap();
         let count = get_count_of_fully_overlapping_pairs(&pairs);
         assert_eq!(2, count);
     }
 
     #[test]
     fn test_get_count_of_overlapping_pairs() {
-        let pairs = get_cleaning_pairs("resources/test/04_cleaning_pairs.txt");
+        let pairs = get_cleaning_pairs("resources/test/04_cleaning_pairs.txt").unwrap();
         let count = get_count_of_all_overlapping_pairs(&pairs);
         assert_eq!(4, count);
     }
+
+    #[test]
+    fn test_coverage_report() {
+        let pairs = get_cleaning_pairs("resources/test/04_cleaning_pairs.txt").unwrap();
+        let report = get_coverage_report(&pairs);
+
+        assert_eq!(12, report.elves);
+        assert_eq!(vec![interval(2, 9)], report.covered.intervals());
+        assert_eq!(8, report.total_covered);
+        assert_eq!(vec![interval(1, 1)], report.uncleaned);
+
+        let report = get_coverage_report(&[cleaning_pair((3, 4), (300, 301)), cleaning_pair((8, 10), (6, 6))]);
+        assert_eq!(8, report.total_covered);
+        assert_eq!(vec![interval(1, 2), interval(5, 5), interval(7, 7), interval(11, 299)], report.uncleaned);
+    }
+
+    #[test]
+    fn test_crowding_report() {
+        let pairs = get_cleaning_pairs("resources/test/04_cleaning_pairs.txt").unwrap();
+
+        // count every section by hand to check the sweep
+        for k in 0..8 {
+            let report = get_crowding_report(&pairs, k);
+            let expected: Vec<u32> = (0..20)
+                .filter(|s| elves(&pairs).filter(|(_, e)| e.contains(*s)).count() > k)
+                .collect();
+            let found: Vec<u32> = (0..20).filter(|s| report.sections.contains(*s)).collect();
+            assert_eq!(expected, found, "k = {}", k);
+            assert_eq!(expected.len() as u64, report.total);
+        }
+
+        let report = get_crowding_report(&pairs, 5);
+        assert_eq!(vec![interval(4, 7)], report.sections.intervals());
+        assert_eq!(8, report.most_elves);
+
+        let report = get_crowding_report(&[cleaning_pair((5, u32::MAX), (u32::MAX, u32::MAX))], 1);
+        assert_eq!(vec![Interval::single(u32::MAX)], report.sections.intervals());
+    }
+
+    #[test]
+    fn test_overlap_report() {
+        let pairs = get_cleaning_pairs("resources/test/04_cleaning_pairs.txt").unwrap();
+        let report = get_overlap_report(&pairs);
+
+        // every two elves compared directly
+        let all: Vec<(ElfId, Interval<u32>)> = elves(&pairs).collect();
+        let mut expected = Vec::new();
+        for (i, (a, first)) in all.iter().enumerate() {
+            for (b, second) in all[i + 1..].iter() {
+                if first.overlaps(second) {
+                    expected.push((*a, *b));
+                }
+            }
+        }
+        assert_eq!(expected, report.overlaps);
+
+        let within: usize = report.len() - report.across_pairs().count();
+        assert_eq!(get_count_of_all_overlapping_pairs(&pairs), within);
+    }
 }
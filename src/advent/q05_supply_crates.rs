@@ -1,5 +1,5 @@
 use crate::common::io;
-use crate::common::str;
+use crate::common::str::{self, Line, ParseError};
 use std::fmt;
 use std::iter;
 
@@ -14,7 +14,7 @@ pub fn process_supplies_plan_from_file(filename: &str, crane_type: Crane) -> Str
         parse_populate_supply_stacks(&lines).expect("Could not parse supply crates");
 
     parse_apply_move_commands(
-        &lines[move_start_line..].to_vec(),
+        str::numbered_lines(&lines).skip(move_start_line),
         &mut supplies,
         crane_type,
     );
@@ -33,9 +33,13 @@ fn parse_populate_supply_stacks(lines: &[String]) -> Option<(Supplies, usize)> {
     None
 }
 
-fn parse_apply_move_commands(lines: &Vec<String>, stacks: &mut Supplies, crane_type: Crane) {
+fn parse_apply_move_commands<'a>(
+    lines: impl Iterator<Item = Line<'a>>,
+    stacks: &mut Supplies,
+    crane_type: Crane,
+) {
     for l in lines {
-        let mv = Move::from_line(l);
+        let mv = Move::from_line(&l).expect("Could not parse move");
         stacks.move_crates(mv, &crane_type);
     }
 }
@@ -85,7 +89,7 @@ impl Supplies {
     }
 
     fn push_line_to_stacks(&mut self, line: &str) {
-        let crate_strs = str::chunk_string(line, Supplies::CRATE_SPACES);
+        let crate_strs = str::columns(line, Supplies::CRATE_SPACES);
 
         for (i, s) in crate_strs.iter().enumerate() {
             // chunk will either be empty or of the form "[X] "
@@ -147,17 +151,20 @@ impl Supplies {
 }
 
 impl Move {
-    fn from_line(l: &str) -> Move {
-        // Example expected format: "move 1 from 2 to 1"
-        let line_split: Vec<&str> = l.split(' ').collect();
-        let parse = |n: &str| -> usize { n.parse().expect("Cannot parse to int") };
+    fn from_line(line: &Line) -> Result<Move, ParseError> {
+        let (amount, from, to): (u8, usize, usize) = line.scan("move {} from {} to {}")?;
 
         // Minus 1 from location numbers to account for vector indexes starting at 0
-        Move {
-            amount: parse(line_split[1]) as u8,
-            from: parse(line_split[3]) - 1,
-            to: parse(line_split[5]) - 1,
-        }
+        let index = |n: usize| {
+            n.checked_sub(1)
+                .ok_or_else(|| line.error("stacks are numbered from 1"))
+        };
+
+        Ok(Move {
+            amount,
+            from: index(from)?,
+            to: index(to)?,
+        })
     }
 }
 
@@ -230,7 +237,17 @@ mod tests {
             to: 0,
         };
 
-        assert_eq!(mv, Move::from_line(mv_str));
+        let line = Line {
+            number: 1,
+            text: mv_str,
+        };
+        assert_eq!(Ok(mv), Move::from_line(&line));
+
+        let line = Line {
+            number: 1,
+            text: "move 1 from 0 to 1",
+        };
+        assert!(Move::from_line(&line).is_err());
     }
 
     #[test]
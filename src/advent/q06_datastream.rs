@@ -1,22 +1,20 @@
-use crate::common::io;
+use crate::common::io::Input;
 use std::collections::{HashSet, VecDeque};
 
 pub fn get_marker_end_index_from_file(filename: &str, marker_size: usize) -> u16 {
-    let mut lines = io::read_file_as_vector(filename).expect("Could not read file");
+    let input = Input::read(filename).expect("Could not read file");
+    let datastream = input.lines().next().expect("File should not be empty");
 
-    find_end_index_of_packet_start_marker(lines.get_mut(0).unwrap(), marker_size)
+    find_end_index_of_packet_start_marker(datastream.text, marker_size)
         .expect("Could not find index")
 }
 
-fn find_end_index_of_packet_start_marker(
-    datastream: &mut String,
-    marker_size: usize,
-) -> Option<u16> {
+fn find_end_index_of_packet_start_marker(datastream: &str, marker_size: usize) -> Option<u16> {
     if datastream.len() < marker_size {
         return None;
     }
 
-    let (initial, rest) = datastream.split_at_mut(marker_size);
+    let (initial, rest) = datastream.split_at(marker_size);
     let mut previous_chars: VecDeque<char> = VecDeque::from(initial.chars().collect::<Vec<char>>());
 
     for (i, c) in rest.chars().enumerate() {
@@ -48,22 +46,16 @@ mod tests {
     #[test]
     fn test_find_end_index() {
         let marker_size = 4;
-        let result = find_end_index_of_packet_start_marker(
-            &mut "bvwbjplbgvbhsrlpgdmjqwftvncz".to_string(),
-            marker_size,
-        );
+        let result =
+            find_end_index_of_packet_start_marker("bvwbjplbgvbhsrlpgdmjqwftvncz", marker_size);
         assert_eq!(5, result.unwrap());
 
-        let result = find_end_index_of_packet_start_marker(
-            &mut "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string(),
-            marker_size,
-        );
+        let result =
+            find_end_index_of_packet_start_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", marker_size);
         assert_eq!(10, result.unwrap());
 
-        let result = find_end_index_of_packet_start_marker(
-            &mut "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string(),
-            marker_size,
-        );
+        let result =
+            find_end_index_of_packet_start_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", marker_size);
         assert_eq!(11, result.unwrap());
     }
 
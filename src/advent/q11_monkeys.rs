@@ -1,5 +1,5 @@
 use crate::common::io;
-use std::num::ParseIntError;
+use crate::common::str::{self, Line, ParseError, Section};
 
 type Monction = Box<dyn Fn(u64) -> u64>; // Monkey function
 
@@ -42,9 +42,9 @@ fn monkey_business_value(monkeys: &mut Vec<Monkey>) -> u64 {
 
 fn parse_file_into_monkeys(filename: &str) -> Vec<Monkey> {
     let lines = io::read_file_as_vector(filename).expect("Could not read file");
-    lines
-        .chunks(7)
-        .map(|monkey_lines| Monkey::from_lines(monkey_lines))
+    str::sections(str::numbered_lines(&lines))
+        .iter()
+        .map(|section| Monkey::from_section(section).expect("Could not parse monkey"))
         .collect()
 }
 
@@ -89,13 +89,15 @@ struct MonkeyTest {
 }
 
 impl Monkey {
-    fn from_lines(lines: &[String]) -> Monkey {
-        Monkey {
-            items: parse_starting_items(&lines[1]),
-            operation: parse_operation(&lines[2]).expect("Should parse operation"),
-            test: parse_monkey_test(&lines[3..=5]).expect("Should parse test"),
+    fn from_section(section: &Section) -> Result<Monkey, ParseError> {
+        let _: (usize,) = section.line(0)?.scan("Monkey {}:")?;
+
+        Ok(Monkey {
+            items: parse_starting_items(&section.line(1)?)?,
+            operation: parse_operation(&section.line(2)?)?,
+            test: parse_monkey_test(&[section.line(3)?, section.line(4)?, section.line(5)?])?,
             inspections: 0,
-        }
+        })
     }
 
     fn test(&self, item: u64) -> usize {
@@ -109,54 +111,54 @@ impl Monkey {
     }
 }
 
-fn parse_starting_items(items: &str) -> Vec<u64> {
+fn parse_starting_items(line: &Line) -> Result<Vec<u64>, ParseError> {
+    let (items,): (String,) = line.scan("Starting items: {}")?;
+
     items
-        .trim()
-        .split(' ')
-        .skip(2)
-        .map(|item| item.trim_end_matches(","))
-        .flat_map(|item| item.parse())
+        .split(',')
+        .map(|item| {
+            item.trim()
+                .parse()
+                .map_err(|_| line.error(format!("cannot parse item '{}'", item.trim())))
+        })
         .collect()
 }
 
-fn parse_operation(operation: &str) -> Result<Monction, &'static str> {
-    let split: Vec<&str> = operation.trim().split(' ').collect();
-
-    if split.len() != 6 {
-        return Err("Cannot parse operation line; not enough tokens.");
-    }
-
-    let (operand, op_value) = (split[4], split[5]);
-    let op_value_int: Result<u64, ParseIntError> = op_value.parse();
+fn parse_operation(line: &Line) -> Result<Monction, ParseError> {
+    const TEMPLATE: &str = "Operation: new = old {} {}";
+    let (operand, op_value): (char, String) = line.scan(TEMPLATE)?;
+    let op_value_int = || {
+        op_value
+            .parse::<u64>()
+            .map_err(|e| line.error(format!("cannot parse '{}': {}", op_value, e)))
+    };
 
-    let func: Monction = match (operand, op_value) {
-        ("*", "old") => Box::new(|x: u64| x * x),
-        ("+", "old") => Box::new(|x: u64| x + x),
-        ("*", _) => Box::new(move |x: u64| x * op_value_int.as_ref().unwrap()),
-        ("+", _) => Box::new(move |x: u64| x + op_value_int.as_ref().unwrap()),
-        (_, _) => return Err("Invalid function operand"),
+    let func: Monction = match (operand, op_value.as_str()) {
+        ('*', "old") => Box::new(|x: u64| x * x),
+        ('+', "old") => Box::new(|x: u64| x + x),
+        ('*', _) => {
+            let value = op_value_int()?;
+            Box::new(move |x: u64| x * value)
+        }
+        ('+', _) => {
+            let value = op_value_int()?;
+            Box::new(move |x: u64| x + value)
+        }
+        (_, _) => return Err(line.error(format!("invalid function operand '{}'", operand))),
     };
 
     Ok(func)
 }
 
-fn parse_monkey_test(test: &[String]) -> Result<MonkeyTest, &'static str> {
-    let test_line: Vec<&str> = test[0].trim().split(' ').collect();
-    let true_line: Vec<&str> = test[1].trim().split(' ').collect();
-    let false_line: Vec<&str> = test[2].trim().split(' ').collect();
-
-    if (test_line.len() != 4) || true_line.len() != 6 || false_line.len() != 6 {
-        return Err("Cannot parse test lines; not enough tokens.");
-    }
-
-    let divisible: u64 = test_line[3].parse().expect("Should parse int");
-    let true_monkey: usize = true_line[5].parse().expect("Should parse int");
-    let false_monkey: usize = false_line[5].parse().expect("Should parse int");
+fn parse_monkey_test(test: &[Line; 3]) -> Result<MonkeyTest, ParseError> {
+    let (divisible,) = test[0].scan("Test: divisible by {}")?;
+    let (if_true,) = test[1].scan("If true: throw to monkey {}")?;
+    let (if_false,) = test[2].scan("If false: throw to monkey {}")?;
 
     Ok(MonkeyTest {
-        divisible: divisible,
-        if_true: true_monkey,
-        if_false: false_monkey,
+        divisible,
+        if_true,
+        if_false,
     })
 }
 
@@ -168,20 +170,27 @@ fn parse_monkey_test(test: &[String]) -> Result<MonkeyTest, &'static str> {
 mod tests {
     use super::*;
 
+    fn line(text: &str) -> Line<'_> {
+        Line { number: 1, text }
+    }
+
     #[test]
     fn test_parse_operation() {
-        let func = parse_operation(&"Operation: new = old + 6").unwrap();
+        let func = parse_operation(&line("Operation: new = old + 6")).unwrap();
         assert_eq!(12, func(6));
-        let func = parse_operation(&"Operation: new = old * 3").unwrap();
+        let func = parse_operation(&line("Operation: new = old * 3")).unwrap();
         assert_eq!(12, func(4));
-        let func = parse_operation(&"Operation: new = old * old").unwrap();
+        let func = parse_operation(&line("Operation: new = old * old")).unwrap();
         assert_eq!(16, func(4));
+        assert!(parse_operation(&line("Operation: new = old - 3")).is_err());
+        let error = parse_operation(&line("Operation: new = old * x")).err().unwrap();
+        assert_eq!("cannot parse 'x': invalid digit found in string", error.message);
     }
 
     #[test]
     fn test_parse_monkey_test() {
-        let test = ["Test: divisible by 13".to_string(), 
-            "If true: throw to monkey 1".into(), "If false: throw to monkey 3".into()];
+        let test = [line("  Test: divisible by 13"),
+            line("    If true: throw to monkey 1"), line("    If false: throw to monkey 3")];
         let test = parse_monkey_test(&test).unwrap();
         assert_eq!(1, test.if_true);
         assert_eq!(3, test.if_false);
@@ -190,10 +199,11 @@ mod tests {
 
     #[test]
     fn test_parse_items() {
-        let result = parse_starting_items(&"Starting items: 79, 60, 97");
-        assert_eq!(vec![79, 60, 97], result);
-        let result = parse_starting_items(&"Starting items: 97");
-        assert_eq!(vec![97], result);
+        let result = parse_starting_items(&line("Starting items: 79, 60, 97"));
+        assert_eq!(Ok(vec![79, 60, 97]), result);
+        let result = parse_starting_items(&line("Starting items: 97"));
+        assert_eq!(Ok(vec![97]), result);
+        assert!(parse_starting_items(&line("Starting items: 97, x")).is_err());
     }
 
     #[test]
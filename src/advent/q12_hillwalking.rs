@@ -1,7 +1,5 @@
 use std::fmt::Display;
 
-use pathfinding::directed::bfs::bfs;
-
 use crate::common::{
     grid::{Grid, Point},
     io,
@@ -9,21 +7,32 @@ use crate::common::{
 
 pub fn get_fewest_steps_from_start(filename: &str) -> usize {
     let nodes = read_file_into_grid(filename);
-    
+
     let start = find_start_node_position(&nodes).expect("Could not find start node");
-    let result = bfs(&start, |n| node_successors(n, &nodes), |n| n.elevation == 'E').expect("Should have a path");
-    
-    result.len() - 1    // remove extra start or end node, idk
+    let path = nodes
+        .bfs(
+            &start,
+            |p| is_end_node(p, &nodes),
+            |from, to| climb_cost(from, to, &nodes),
+        )
+        .expect("Should have a path");
+
+    path.steps()
 }
 
 pub fn get_fewest_steps_from_low_elevation(filename: &str) -> usize {
     let nodes = read_file_into_grid(filename);
 
-    find_a_nodes_at_edge(&nodes)
+    let end = find_end_node_position(&nodes).expect("Could not find end node");
+    let steps_to_end = nodes.distance_map_to(&[end], |from, to| {
+        climb_cost(from, to, &nodes).is_some()
+    });
+
+    find_low_nodes(&nodes)
         .iter()
-        .flat_map(|start| bfs(start, |n| node_successors(n, &nodes), |n| n.elevation == 'E'))
-        .map(|path| path.len() - 1)
-        .min().expect("Should have min")
+        .flat_map(|p| steps_to_end.get_element(p).copied().flatten())
+        .min()
+        .expect("Should have min")
 }
 
 fn read_file_into_grid(filename: &str) -> Grid<Node> {
@@ -45,34 +54,26 @@ fn read_file_into_grid(filename: &str) -> Grid<Node> {
 //     }
 // }
 
-fn node_successors<'a>(current: &Node, nodes: &'a Grid<Node>) -> Vec<&'a Node> {
-    nodes
-        .get_adjacent_points(&current.position)
-        .iter()
-        .flat_map(|p| nodes.get_element(p))
-        .filter(|n| current.distance_from(&n).is_some())
-        .collect()
+fn is_end_node(point: &Point, nodes: &Grid<Node>) -> bool {
+    nodes.get_element(point).is_some_and(|n| n.elevation == 'E')
 }
 
-fn find_start_node_position(nodes: &Grid<Node>) -> Option<&Node> {
-    for n in nodes.elements.iter() {
-        if n.elevation == 'S' {
-            return Some(n);
-        }
-    }
-    None
+// Every climbable move is a single step.
+fn climb_cost(from: &Point, to: &Point, nodes: &Grid<Node>) -> Option<usize> {
+    let (current, next) = (nodes.get_element(from)?, nodes.get_element(to)?);
+    current.distance_from(next).map(|_| 1)
 }
 
-fn find_a_nodes_at_edge(nodes: &Grid<Node>) -> Vec<&Node> {
-    let mut a_nodes = Vec::new();
+fn find_start_node_position(nodes: &Grid<Node>) -> Option<Point> {
+    nodes.find(|n| n.elevation == 'S')
+}
 
-    for n in nodes.elements.iter() {
-        if (n.elevation == 'a' || n.elevation == 'S') && nodes.is_edge_node(&n.position) {
-            a_nodes.push(n);
-        }
-    }
+fn find_end_node_position(nodes: &Grid<Node>) -> Option<Point> {
+    nodes.find(|n| n.elevation == 'E')
+}
 
-    a_nodes
+fn find_low_nodes(nodes: &Grid<Node>) -> Vec<Point> {
+    nodes.positions(|n| n.elevation == 'a' || n.elevation == 'S')
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -163,12 +164,12 @@ mod tests {
     fn test_find_start_node() {
         let grid = read_file_into_grid("resources/test/12_hillwalking.txt");
         assert_eq!(
-            find_start_node_position(&grid).unwrap().position,
+            find_start_node_position(&grid).unwrap(),
             Point { x: 0, y: 0 }
         );
         let grid = read_file_into_grid("resources/test/12_hillwalking_modified_test.txt");
         let point = Point { x: 5, y: 3 };
-        assert_eq!(find_start_node_position(&grid).unwrap().position, point);
+        assert_eq!(find_start_node_position(&grid).unwrap(), point);
 
         let node = grid.get_element(&point);
         assert_eq!(node.unwrap().elevation, 'S');
@@ -210,8 +211,14 @@ mod tests {
     #[test]
     fn test_get_a_nodes() {
         let grid = read_file_into_grid("resources/test/12_hillwalking.txt");
-        let result = find_a_nodes_at_edge(&grid);
+        let result = find_low_nodes(&grid);
         assert_eq!(result.len(), 6);
+
+        // the modified test input has low nodes away from the edge too
+        let grid = read_file_into_grid("resources/test/12_hillwalking_modified_test.txt");
+        assert!(find_low_nodes(&grid)
+            .iter()
+            .any(|n| !grid.is_edge_node(n)));
     }
 
     #[test]
@@ -1,3 +1,7 @@
+pub mod automaton;
 pub mod grid;
+pub mod grid3;
+pub mod interval;
 pub mod io;
+pub mod point;
 pub mod str;
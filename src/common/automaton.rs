@@ -0,0 +1,429 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use crate::common::grid::sparse::{SparseGrid, SparsePoint};
+use crate::common::grid::{Grid, Neighbourhood};
+
+/// Automaton steps a board one generation at a time. Every cell's next value is given by a rule, which sees
+/// the cell's current value and the values of its neighbours. All cells change at once: the next generation
+/// is written to a second buffer, which is then swapped with the current one.
+///
+/// The board is either a `Grid`, which has a fixed size and follows its topology at the edges, or a
+/// `SparseGrid`, which grows as needed. Cells missing from a sparse grid have the default value, and cells
+/// whose value becomes the default are removed.
+pub struct Automaton<'a, B: Board, R> {
+    board: B,
+    back: B,
+    rule: R,
+    neighbourhood: Neighbourhood,
+    generation: usize,
+    /// The board at generation 0. A hash match is confirmed by stepping it again to the earlier generation,
+    /// so that a collision isn't taken for a cycle without keeping every board.
+    initial: B,
+    /// Generations seen so far, by the hash of their board, until a cycle is found.
+    seen: HashMap<u64, Vec<usize>>,
+    cycle: Option<Cycle>,
+    recorders: Vec<Recorder<'a, B>>,
+}
+
+type Recorder<'a, B> = Box<dyn FnMut(usize, &B) + 'a>;
+
+/// A repeating run of generations. The board at `start + length` is the same as the board at `start`.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+/// A board that an `Automaton` can step.
+pub trait Board: Clone + PartialEq {
+    type Cell: Clone + PartialEq + Hash;
+
+    /// Write the generation after this one to `next`. Returns true if any cell changed.
+    fn step_into<R>(&self, next: &mut Self, neighbourhood: Neighbourhood, rule: &R) -> bool
+    where
+        R: Fn(&Self::Cell, &[&Self::Cell]) -> Self::Cell;
+
+    /// Hash of every cell, used to spot a board that has been seen before.
+    fn state_hash(&self) -> u64;
+}
+
+#[allow(dead_code)]
+impl<'a, B, R> Automaton<'a, B, R>
+where
+    B: Board,
+    R: Fn(&B::Cell, &[&B::Cell]) -> B::Cell,
+{
+    pub fn new(board: B, neighbourhood: Neighbourhood, rule: R) -> Self {
+        let mut seen = HashMap::new();
+        seen.insert(board.state_hash(), vec![0]);
+
+        Automaton {
+            back: board.clone(),
+            initial: board.clone(),
+            board,
+            rule,
+            neighbourhood,
+            generation: 0,
+            seen,
+            cycle: None,
+            recorders: Vec::new(),
+        }
+    }
+
+    /// Call `recorder` with the generation number and board after every step, e.g. to save frames for
+    /// rendering. Once `fast_forward` has found a cycle, the generations it passes on the way to its target
+    /// are not recorded.
+    pub fn with_recorder<F>(mut self, recorder: F) -> Self
+    where
+        F: FnMut(usize, &B) + 'a,
+    {
+        self.recorders.push(Box::new(recorder));
+        self
+    }
+
+    pub fn board(&self) -> &B {
+        &self.board
+    }
+
+    pub fn into_board(self) -> B {
+        self.board
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The first cycle seen while stepping, if there has been one.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Step one generation. Returns true if any cell changed.
+    pub fn step(&mut self) -> bool {
+        let changed = self.advance();
+        if self.cycle.is_none() {
+            self.look_for_cycle();
+        }
+
+        for recorder in self.recorders.iter_mut() {
+            recorder(self.generation, &self.board);
+        }
+
+        changed
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        (0..generations).for_each(|_| {
+            self.step();
+        });
+    }
+
+    /// Step until a generation where nothing changes, or `max_generations` steps. Returns the number of
+    /// steps taken if the board settled.
+    pub fn run_until_stable(&mut self, max_generations: usize) -> Option<usize> {
+        (1..=max_generations).find(|_| !self.step())
+    }
+
+    /// Step until a board repeats, or `max_generations` steps.
+    pub fn find_cycle(&mut self, max_generations: usize) -> Option<Cycle> {
+        for _ in 0..max_generations {
+            if self.cycle.is_some() {
+                break;
+            }
+            self.step();
+        }
+
+        self.cycle
+    }
+
+    /// Step to `generation`. Once a cycle is found, whole cycles are skipped rather than stepped, so very
+    /// late generations can be reached quickly.
+    pub fn fast_forward(&mut self, generation: usize) {
+        while self.generation < generation {
+            if let Some(cycle) = self.cycle {
+                let remaining = (generation - self.generation) % cycle.length;
+                (0..remaining).for_each(|_| {
+                    self.advance();
+                });
+                self.generation = generation;
+                return;
+            }
+
+            self.step();
+        }
+    }
+
+    fn look_for_cycle(&mut self) {
+        let hash = self.board.state_hash();
+        let candidates = self.seen.get(&hash).cloned().unwrap_or_default();
+
+        match candidates
+            .into_iter()
+            .find(|start| self.replay(*start) == self.board)
+        {
+            Some(start) => {
+                self.cycle = Some(Cycle {
+                    start,
+                    length: self.generation - start,
+                });
+                // the cycle is all that was needed from the hashes
+                self.seen = HashMap::new();
+            }
+            None => self.seen.entry(hash).or_default().push(self.generation),
+        }
+    }
+
+    // the board at `generation`, stepped again from the first board
+    fn replay(&self, generation: usize) -> B {
+        let mut board = self.initial.clone();
+        let mut next = self.initial.clone();
+        for _ in 0..generation {
+            board.step_into(&mut next, self.neighbourhood, &self.rule);
+            std::mem::swap(&mut board, &mut next);
+        }
+
+        board
+    }
+
+    // step without looking for cycles or calling recorders
+    fn advance(&mut self) -> bool {
+        let changed = self
+            .board
+            .step_into(&mut self.back, self.neighbourhood, &self.rule);
+        std::mem::swap(&mut self.board, &mut self.back);
+        self.generation += 1;
+
+        changed
+    }
+}
+
+impl<T: Clone + PartialEq + Hash> Board for Grid<T> {
+    type Cell = T;
+
+    fn step_into<R>(&self, next: &mut Self, neighbourhood: Neighbourhood, rule: &R) -> bool
+    where
+        R: Fn(&T, &[&T]) -> T,
+    {
+        if next.elements.len() != self.elements.len() {
+            *next = self.clone();
+        }
+
+        let mut changed = false;
+        for (index, value) in self.elements.iter().enumerate() {
+            let point = self.index_to_point(index).expect("Index should be in grid");
+            let neighbours: Vec<&T> = self
+                .get_neighbours(&point, neighbourhood)
+                .iter()
+                .flat_map(|p| self.get_element(p))
+                .collect();
+
+            let new = rule(value, &neighbours);
+            changed |= new != *value;
+            next.elements[index] = new;
+        }
+
+        changed
+    }
+
+    fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.columns.hash(&mut hasher);
+        self.elements.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl<T: Clone + PartialEq + Hash + Default> Board for SparseGrid<T> {
+    type Cell = T;
+
+    fn step_into<R>(&self, next: &mut Self, neighbourhood: Neighbourhood, rule: &R) -> bool
+    where
+        R: Fn(&T, &[&T]) -> T,
+    {
+        let empty = T::default();
+        let value_at = |p: &SparsePoint| self.get(p).unwrap_or(&empty);
+
+        // only set cells and their neighbours can become set
+        let candidates: HashSet<SparsePoint> = self
+            .iter()
+            .flat_map(|(p, _)| {
+                let mut points = self.get_neighbours(p, neighbourhood);
+                points.push(*p);
+                points
+            })
+            .collect();
+
+        next.clear();
+        let mut changed = false;
+        for point in candidates {
+            let value = value_at(&point);
+            let neighbours: Vec<&T> = self
+                .get_neighbours(&point, neighbourhood)
+                .iter()
+                .map(value_at)
+                .collect();
+
+            let new = rule(value, &neighbours);
+            changed |= new != *value;
+            if new != empty {
+                next.set(point, new);
+            }
+        }
+
+        changed
+    }
+
+    fn state_hash(&self) -> u64 {
+        let mut cells: Vec<(&SparsePoint, &T)> = self.iter().collect();
+        cells.sort_by_key(|(p, _)| **p);
+
+        let mut hasher = DefaultHasher::new();
+        cells.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::grid::topology::Topology;
+
+    fn life(alive: &bool, neighbours: &[&bool]) -> bool {
+        let count = neighbours.iter().filter(|n| ***n).count();
+        count == 3 || (*alive && count == 2)
+    }
+
+    fn read_board(rows: &[&str]) -> Grid<bool> {
+        let mut grid = Grid::new();
+        rows.iter()
+            .for_each(|r| grid.add_row(r.chars().map(|c| c == '#').collect()));
+        grid
+    }
+
+    #[test]
+    fn test_blinker_on_grid() {
+        let board = read_board(&[".....", "..#..", "..#..", "..#..", "....."]);
+        let mut automaton = Automaton::new(board, Neighbourhood::All, life);
+
+        assert!(automaton.step());
+        assert_eq!(
+            vec![false, true, true, true, false],
+            automaton.board().get_row(2).unwrap()
+        );
+        assert_eq!(3, automaton.board().count(|c| *c));
+
+        assert_eq!(
+            Some(Cycle {
+                start: 0,
+                length: 2
+            }),
+            automaton.find_cycle(10)
+        );
+        assert_eq!(2, automaton.generation());
+    }
+
+    #[test]
+    fn test_run_until_stable() {
+        // a block never changes, and the lone cell dies on the first step
+        let board = read_board(&["##...", "##...", ".....", "....#"]);
+        let mut automaton = Automaton::new(board, Neighbourhood::All, life);
+
+        assert_eq!(Some(2), automaton.run_until_stable(10));
+        assert_eq!(4, automaton.board().count(|c| *c));
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        // a glider on a 6x6 torus comes back to where it started after 24 generations
+        let board = read_board(&[".#....", "..#...", "###...", "......", "......", "......"])
+            .with_topology(Topology::Toroidal);
+        let start = board.clone();
+        let mut recorded = Vec::new();
+        let mut automaton = Automaton::new(board, Neighbourhood::All, life)
+            .with_recorder(|generation, _: &Grid<bool>| recorded.push(generation));
+
+        automaton.fast_forward(1_000_000_000);
+        assert_eq!(1_000_000_000, automaton.generation());
+        assert_eq!(
+            Some(Cycle {
+                start: 0,
+                length: 24
+            }),
+            automaton.cycle()
+        );
+
+        let mut expected = Automaton::new(start, Neighbourhood::All, life);
+        expected.run(16);
+        assert_eq!(expected.board(), automaton.board());
+
+        // only the steps taken to find the cycle are recorded, and stepping on carries on from the target
+        automaton.step();
+        drop(automaton);
+        assert_eq!(
+            (1..=24).chain([1_000_000_001]).collect::<Vec<_>>(),
+            recorded
+        );
+    }
+
+    // counts from 0 to 4 and wraps round, with every count hashing the same
+    #[derive(Clone, PartialEq)]
+    struct Counter(u8);
+
+    impl Board for Counter {
+        type Cell = u8;
+
+        fn step_into<R>(&self, next: &mut Self, _: Neighbourhood, _: &R) -> bool {
+            next.0 = (self.0 + 1) % 5;
+            true
+        }
+
+        fn state_hash(&self) -> u64 {
+            0
+        }
+    }
+
+    #[test]
+    fn test_hash_collision_is_not_a_cycle() {
+        let mut automaton = Automaton::new(Counter(0), Neighbourhood::All, |c: &u8, _: &[&u8]| *c);
+
+        assert_eq!(
+            Some(Cycle {
+                start: 0,
+                length: 5
+            }),
+            automaton.find_cycle(10)
+        );
+        automaton.fast_forward(1003);
+        assert_eq!(3, automaton.board().0);
+    }
+
+    #[test]
+    fn test_glider_on_sparse_grid() {
+        let mut board = SparseGrid::new();
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            board.set(SparsePoint::new(x, y), true);
+        }
+
+        let mut frames = Vec::new();
+        let mut automaton = Automaton::new(board, Neighbourhood::All, life).with_recorder(
+            |generation, board: &SparseGrid<bool>| frames.push((generation, board.len())),
+        );
+
+        // a glider moves one cell down and right every four generations, and never repeats
+        automaton.run(8);
+        assert_eq!(None, automaton.cycle());
+        let board = automaton.into_board();
+        assert_eq!(5, board.len());
+        assert_eq!(SparsePoint::new(2, 2), board.bounds().unwrap().min);
+        assert_eq!(SparsePoint::new(4, 4), board.bounds().unwrap().max);
+
+        assert_eq!(8, frames.len());
+        assert_eq!((1, 5), frames[0]);
+        assert!(frames.iter().all(|(_, cells)| *cells == 5));
+    }
+}
@@ -1,15 +1,31 @@
 use std::fmt::Display;
 
+pub mod distance;
+pub mod path;
+pub mod ray;
+pub mod region;
+pub mod render;
+pub mod sparse;
+pub mod topology;
+pub mod transform;
+pub mod view;
+
+pub use crate::common::point::Point;
+use topology::Topology;
+
 /// Grid is a 2-dimensional, row-major ordered array. Column size is fixed, but it can have as many rows as you want.
 /// Rows are added with the `add_row` function.
 ///
 /// Column size can be set on instatiation using `with_column_size`. If column size is not set, it will be set as the
 /// length of the first row added to the grid.
-#[derive(Debug)]
+///
+/// By default the edges of the grid are walls. Use `with_topology` to make moves off an edge wrap around.
+#[derive(Debug, Clone)]
 pub struct Grid<T> {
     pub elements: Vec<T>,
     pub columns: usize,
     pub rows: usize,
+    pub topology: Topology,
 }
 
 #[allow(dead_code)]
@@ -19,6 +35,17 @@ impl<T: Clone> Grid<T> {
             elements: Vec::<T>::new(),
             columns: 0,
             rows: 0,
+            topology: Topology::Bounded,
+        }
+    }
+
+    /// Create a grid of the given size with every element set to `value`.
+    pub fn filled(columns: usize, rows: usize, value: T) -> Self {
+        Grid {
+            elements: vec![value; columns * rows],
+            columns,
+            rows,
+            topology: Topology::Bounded,
         }
     }
 
@@ -120,30 +147,18 @@ impl<T: Clone> Grid<T> {
     }
 
     pub fn get_adjacent_points(&self, point: &Point) -> Vec<Point> {
-        let mut points = Vec::<Point>::new();
-
-        if point.x >= self.columns || point.y >= self.rows {
-            return points;
-        }
-
-        let is_in_bounds = |p: Point| p.x < self.columns && p.y < self.rows;
-
-        for i in -1..=1 {
-            for j in -1..=1 {
-                // skip diagonals for now...
-                if i == j || i + j == 0 {
-                    continue;
-                }
+        self.get_neighbours(point, Neighbourhood::Orthogonal)
+    }
 
-                if (point.x as isize + i < 0) || (point.y as isize + j < 0) {
-                    continue;
-                }
+    /// Get the points around `point` for the given neighbourhood. Only in-bounds points are returned, unless
+    /// the grid's topology wraps the edges.
+    pub fn get_neighbours(&self, point: &Point, neighbourhood: Neighbourhood) -> Vec<Point> {
+        let mut points = Vec::<Point>::new();
 
-                let p = Point {
-                    x: (point.x as isize + i) as usize,
-                    y: (point.y as isize + j) as usize,
-                };
-                if is_in_bounds(p) {
+        for direction in neighbourhood.directions() {
+            if let Some((p, _)) = self.step(point, *direction) {
+                // a small wrapping grid can reach the same point in more than one direction
+                if !points.contains(&p) {
                     points.push(p);
                 }
             }
@@ -157,15 +172,118 @@ impl<T: Clone> Grid<T> {
     }
 }
 
-#[derive(Eq, PartialEq, Clone, Copy, Debug, Ord, PartialOrd, Hash)]
-pub struct Point {
-    pub x: usize,
-    pub y: usize,
+/// Which of the surrounding cells count as neighbours of a point.
+#[allow(dead_code)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Neighbourhood {
+    /// Left, up, down and right.
+    Orthogonal,
+    /// The four diagonal cells only.
+    Diagonal,
+    /// All eight surrounding cells.
+    All,
 }
 
-impl Display for Point {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "(x: {}, y: {})", self.x, self.y)
+impl Neighbourhood {
+    const ORTHOGONAL: [Direction; 4] = [
+        Direction::Left,
+        Direction::Up,
+        Direction::Down,
+        Direction::Right,
+    ];
+    const DIAGONAL: [Direction; 4] = [
+        Direction::UpLeft,
+        Direction::DownLeft,
+        Direction::UpRight,
+        Direction::DownRight,
+    ];
+    const ALL: [Direction; 8] = [
+        Direction::UpLeft,
+        Direction::Left,
+        Direction::DownLeft,
+        Direction::Up,
+        Direction::Down,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+    ];
+
+    /// Directions from the centre point to each neighbour, in a fixed order.
+    pub fn directions(&self) -> &'static [Direction] {
+        match self {
+            Neighbourhood::Orthogonal => &Self::ORTHOGONAL,
+            Neighbourhood::Diagonal => &Self::DIAGONAL,
+            Neighbourhood::All => &Self::ALL,
+        }
+    }
+}
+
+/// A compass direction on the grid. Rows are numbered downwards, so `Up` moves towards row 0.
+#[allow(dead_code)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+#[allow(dead_code)]
+impl Direction {
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+    /// All eight directions, clockwise from `Up`.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// (x, y) change for a single step in this direction.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::UpRight => (1, -1),
+            Direction::Right => (1, 0),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (0, 1),
+            Direction::DownLeft => (-1, 1),
+            Direction::Left => (-1, 0),
+            Direction::UpLeft => (-1, -1),
+        }
+    }
+
+    /// Turn 90 degrees clockwise.
+    pub fn turn_right(&self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Turn 90 degrees anticlockwise.
+    pub fn turn_left(&self) -> Self {
+        self.rotate(6)
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate(4)
+    }
+
+    // Rotate clockwise by the given number of eighth turns.
+    fn rotate(&self, eighths: usize) -> Self {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap();
+        Self::ALL[(index + eighths) % 8]
     }
 }
 
@@ -314,6 +432,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_neighbours() {
+        let grid = get_test_grid();
+        assert_eq!(
+            grid.get_neighbours(&Point { x: 0, y: 0 }, Neighbourhood::All),
+            vec![
+                Point { x: 0, y: 1 },
+                Point { x: 1, y: 0 },
+                Point { x: 1, y: 1 }
+            ]
+        );
+        assert_eq!(
+            grid.get_neighbours(&Point { x: 0, y: 0 }, Neighbourhood::Diagonal),
+            vec![Point { x: 1, y: 1 }]
+        );
+        assert_eq!(
+            8,
+            grid.get_neighbours(&Point { x: 1, y: 1 }, Neighbourhood::All)
+                .len()
+        );
+        assert_eq!(
+            4,
+            grid.get_neighbours(&Point { x: 2, y: 2 }, Neighbourhood::Diagonal)
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_direction_turns() {
+        assert_eq!(Direction::Right, Direction::Up.turn_right());
+        assert_eq!(Direction::Left, Direction::Up.turn_left());
+        assert_eq!(Direction::UpLeft, Direction::DownLeft.turn_right());
+        assert_eq!(Direction::Down, Direction::Up.opposite());
+        assert_eq!(Direction::DownRight, Direction::UpLeft.opposite());
+
+        for d in Direction::ALL {
+            assert_eq!(d, d.turn_left().turn_right());
+            let (x, y) = d.offset();
+            assert_eq!((-x, -y), d.opposite().offset());
+        }
+    }
+
     //grid.add_row(vec![0, 0, 1, 5]);
     // grid.add_row(vec![1, 3, 1, 7]);
     // grid.add_row(vec![8, 7, 1, 10]);
@@ -322,7 +482,7 @@ mod tests {
 
     #[test]
     fn test_is_edge_node() {
-        let grid = get_test_grid(); 
+        let grid = get_test_grid();
         assert!(grid.is_edge_node(&Point { x: 0, y: 0 }));
         assert!(grid.is_edge_node(&Point { x: 0, y: 4 }));
         assert!(grid.is_edge_node(&Point { x: 3, y: 4 }));
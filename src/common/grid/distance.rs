@@ -0,0 +1,192 @@
+use std::collections::VecDeque;
+
+use super::{Grid, Point};
+
+/// Which way moves are followed when building a distance map.
+///     Forwards: distances are the steps needed to get from the nearest source to each cell.
+///     Backwards: distances are the steps needed to get from each cell to the nearest source, e.g. a goal.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Flow {
+    Forwards,
+    Backwards,
+}
+
+/// The closest source to a cell, and how many steps away it is.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Nearest {
+    pub source: Point,
+    pub distance: usize,
+}
+
+/// Distance maps are built with one breadth-first pass from all of the sources at once. `passable` is
+/// given the point moved from and the point moved to, and returns true if that move is allowed.
+///
+/// Backwards maps follow moves in reverse, which assumes that neighbours are symmetric. This holds for
+/// bounded and toroidal grids, but may not for a custom topology.
+#[allow(dead_code)]
+impl<T: Clone> Grid<T> {
+    /// Fewest steps from any of `sources` to each cell. None for cells that can't be reached.
+    pub fn distance_map<P>(&self, sources: &[Point], passable: P) -> Grid<Option<usize>>
+    where
+        P: Fn(&Point, &Point) -> bool,
+    {
+        self.to_distances(self.nearest_source_map(sources, passable, Flow::Forwards))
+    }
+
+    /// Fewest steps from each cell to any of `goals`. None for cells that can't reach a goal.
+    pub fn distance_map_to<P>(&self, goals: &[Point], passable: P) -> Grid<Option<usize>>
+    where
+        P: Fn(&Point, &Point) -> bool,
+    {
+        self.to_distances(self.nearest_source_map(goals, passable, Flow::Backwards))
+    }
+
+    /// The nearest source, and distance to it, for each cell. Where two sources are equally near, the one
+    /// listed first in `sources` wins.
+    pub fn nearest_source_map<P>(
+        &self,
+        sources: &[Point],
+        passable: P,
+        flow: Flow,
+    ) -> Grid<Option<Nearest>>
+    where
+        P: Fn(&Point, &Point) -> bool,
+    {
+        let mut nearest: Grid<Option<Nearest>> = Grid::filled(self.columns, self.rows, None);
+        let mut queue = VecDeque::new();
+
+        for source in sources {
+            if let Some(cell @ None) = nearest.get_element_mut(source) {
+                *cell = Some(Nearest {
+                    source: *source,
+                    distance: 0,
+                });
+                queue.push_back(*source);
+            }
+        }
+
+        while let Some(current) = queue.pop_front() {
+            let here = nearest
+                .get_element(&current)
+                .copied()
+                .flatten()
+                .expect("Queued points have a distance");
+
+            for next in self.get_adjacent_points(&current) {
+                let allowed = match flow {
+                    Flow::Forwards => passable(&current, &next),
+                    Flow::Backwards => passable(&next, &current),
+                };
+
+                if let Some(cell @ None) = nearest.get_element_mut(&next) {
+                    if allowed {
+                        *cell = Some(Nearest {
+                            source: here.source,
+                            distance: here.distance + 1,
+                        });
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        nearest
+    }
+
+    fn to_distances(&self, nearest: Grid<Option<Nearest>>) -> Grid<Option<usize>> {
+        nearest.map(|n| n.map(|n| n.distance))
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Heights. You can step down any amount, but only up by one.
+    fn get_test_grid() -> Grid<u8> {
+        let mut grid = Grid::<u8>::new();
+        grid.add_row(vec![0, 1, 2, 3]);
+        grid.add_row(vec![5, 9, 9, 4]);
+        grid.add_row(vec![0, 0, 0, 0]);
+        grid
+    }
+
+    fn climbable(grid: &Grid<u8>) -> impl Fn(&Point, &Point) -> bool + '_ {
+        |from, to| match (grid.get_element(from), grid.get_element(to)) {
+            (Some(a), Some(b)) => *b <= a + 1,
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_distance_map_forwards() {
+        let grid = get_test_grid();
+        let distances = grid.distance_map(&[Point { x: 0, y: 0 }], climbable(&grid));
+
+        assert_eq!(
+            vec![Some(0), Some(1), Some(2), Some(3)],
+            distances.get_row(0).unwrap()
+        );
+        // 5 is too high to climb to from 0, and the 9s from anywhere
+        assert_eq!(
+            vec![None, None, None, Some(4)],
+            distances.get_row(1).unwrap()
+        );
+        assert_eq!(
+            vec![Some(8), Some(7), Some(6), Some(5)],
+            distances.get_row(2).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_distance_map_backwards() {
+        let grid = get_test_grid();
+        let distances = grid.distance_map_to(&[Point { x: 3, y: 0 }], climbable(&grid));
+
+        assert_eq!(
+            vec![Some(3), Some(2), Some(1), Some(0)],
+            distances.get_row(0).unwrap()
+        );
+        // the 5 can step down onto the 0 above it, but nothing on the bottom row can climb back up
+        assert_eq!(
+            vec![Some(4), Some(3), Some(2), Some(1)],
+            distances.get_row(1).unwrap()
+        );
+        assert_eq!(vec![None; 4], distances.get_row(2).unwrap());
+    }
+
+    #[test]
+    fn test_nearest_source_map() {
+        let grid = get_test_grid();
+        let left = Point { x: 0, y: 2 };
+        let right = Point { x: 2, y: 2 };
+
+        let nearest = grid.nearest_source_map(&[left, right], |_, _| true, Flow::Forwards);
+
+        assert_eq!(
+            Some(&Some(Nearest {
+                source: left,
+                distance: 1
+            })),
+            nearest.get_element(&Point { x: 0, y: 1 })
+        );
+        assert_eq!(
+            Some(&Some(Nearest {
+                source: right,
+                distance: 3
+            })),
+            nearest.get_element(&Point { x: 3, y: 0 })
+        );
+        // equally near to both, so the first source wins
+        assert_eq!(
+            Some(&Some(Nearest {
+                source: left,
+                distance: 3
+            })),
+            nearest.get_element(&Point { x: 1, y: 0 })
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+use pathfinding::directed::{astar::astar, bfs::bfs, dijkstra::dijkstra, dijkstra::dijkstra_all};
+
+use super::{Grid, Point};
+
+/// A route found by one of the grid searches.
+///
+/// `points` includes both the start and the goal, so a path from a point to itself has one point and
+/// zero steps. Use `steps()` rather than `points.len()` when you want the number of moves made.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Path {
+    pub points: Vec<Point>,
+    pub cost: usize,
+}
+
+#[allow(dead_code)]
+impl Path {
+    /// Number of moves taken along the path: one less than the number of points.
+    pub fn steps(&self) -> usize {
+        self.points.len() - 1
+    }
+
+    pub fn start(&self) -> &Point {
+        self.points.first().expect("Path should have a start")
+    }
+
+    pub fn end(&self) -> &Point {
+        self.points.last().expect("Path should have an end")
+    }
+}
+
+/// Cheapest cost to reach every point of a grid from a set of sources, along with the moves needed to
+/// rebuild the route to any reached point.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct CostMap {
+    pub costs: Grid<Option<usize>>,
+    parents: HashMap<Point, Point>,
+}
+
+#[allow(dead_code)]
+impl CostMap {
+    pub fn cost(&self, point: &Point) -> Option<usize> {
+        self.costs.get_element(point).copied().flatten()
+    }
+
+    /// Rebuild the cheapest route from its nearest source to `point`. None if `point` was not reached.
+    pub fn path_to(&self, point: &Point) -> Option<Path> {
+        let cost = self.cost(point)?;
+        let mut points = vec![*point];
+
+        while let Some(parent) = self.parents.get(points.last().unwrap()) {
+            points.push(*parent);
+        }
+        points.reverse();
+
+        Some(Path { points, cost })
+    }
+}
+
+/// The searches all move between orthogonally adjacent points. They take:
+///     goal: returns true for the point (or points) we want to reach.
+///     cost: returns the cost of moving from the first point to the second, or None if the move is not allowed.
+///
+/// Every search is run over `Option<Point>`, where `None` is a virtual start joined to each of the real
+/// starts at zero cost. This lets single and multi-source searches share one implementation.
+#[allow(dead_code)]
+impl<T: Clone> Grid<T> {
+    /// Breadth-first search for the fewest steps to a goal. Every allowed move counts as one step, so the
+    /// value returned by `cost` is only used to decide if a move is allowed.
+    pub fn bfs<G, C>(&self, start: &Point, goal: G, cost: C) -> Option<Path>
+    where
+        G: Fn(&Point) -> bool,
+        C: Fn(&Point, &Point) -> Option<usize>,
+    {
+        self.bfs_from_any(&[*start], goal, cost)
+    }
+
+    /// As `bfs`, but the path may begin at any of `starts`.
+    pub fn bfs_from_any<G, C>(&self, starts: &[Point], goal: G, cost: C) -> Option<Path>
+    where
+        G: Fn(&Point) -> bool,
+        C: Fn(&Point, &Point) -> Option<usize>,
+    {
+        let nodes = bfs(
+            &None,
+            |n| {
+                self.search_successors(n, starts, &cost)
+                    .into_iter()
+                    .map(|(p, _)| p)
+            },
+            |n| n.as_ref().is_some_and(&goal),
+        )?;
+
+        let points = strip_virtual_start(nodes);
+        let cost = points.len() - 1;
+
+        Some(Path { points, cost })
+    }
+
+    /// Dijkstra search for the cheapest path to a goal.
+    pub fn dijkstra<G, C>(&self, start: &Point, goal: G, cost: C) -> Option<Path>
+    where
+        G: Fn(&Point) -> bool,
+        C: Fn(&Point, &Point) -> Option<usize>,
+    {
+        self.dijkstra_from_any(&[*start], goal, cost)
+    }
+
+    /// As `dijkstra`, but the path may begin at any of `starts`.
+    pub fn dijkstra_from_any<G, C>(&self, starts: &[Point], goal: G, cost: C) -> Option<Path>
+    where
+        G: Fn(&Point) -> bool,
+        C: Fn(&Point, &Point) -> Option<usize>,
+    {
+        let (nodes, cost) = dijkstra(
+            &None,
+            |n| self.search_successors(n, starts, &cost),
+            |n| n.as_ref().is_some_and(&goal),
+        )?;
+
+        Some(Path {
+            points: strip_virtual_start(nodes),
+            cost,
+        })
+    }
+
+    /// A* search for the cheapest path to a goal. `heuristic` estimates the remaining cost from a point to
+    /// the goal, and must never overestimate it for the result to be the cheapest path.
+    pub fn astar<G, C, H>(&self, start: &Point, goal: G, cost: C, heuristic: H) -> Option<Path>
+    where
+        G: Fn(&Point) -> bool,
+        C: Fn(&Point, &Point) -> Option<usize>,
+        H: Fn(&Point) -> usize,
+    {
+        let (nodes, cost) = astar(
+            &None,
+            |n| self.search_successors(n, &[*start], &cost),
+            |n| n.as_ref().map_or(0, &heuristic),
+            |n| n.as_ref().is_some_and(&goal),
+        )?;
+
+        Some(Path {
+            points: strip_virtual_start(nodes),
+            cost,
+        })
+    }
+
+    /// Cheapest cost from any of `starts` to every point in the grid.
+    pub fn cost_map<C>(&self, starts: &[Point], cost: C) -> CostMap
+    where
+        C: Fn(&Point, &Point) -> Option<usize>,
+    {
+        let reached = dijkstra_all(&None, |n| self.search_successors(n, starts, &cost));
+
+        let mut costs = Grid::filled(self.columns, self.rows, None);
+
+        let mut parents = HashMap::new();
+
+        for (node, (parent, c)) in reached {
+            let point = node.expect("Virtual start is never reached");
+            *costs
+                .get_element_mut(&point)
+                .expect("Point should be in grid") = Some(c);
+
+            if let Some(parent) = parent {
+                parents.insert(point, parent);
+            }
+        }
+
+        CostMap { costs, parents }
+    }
+
+    fn search_successors<C>(
+        &self,
+        node: &Option<Point>,
+        starts: &[Point],
+        cost: &C,
+    ) -> Vec<(Option<Point>, usize)>
+    where
+        C: Fn(&Point, &Point) -> Option<usize>,
+    {
+        match node {
+            None => starts.iter().map(|p| (Some(*p), 0)).collect(),
+            Some(current) => self
+                .get_adjacent_points(current)
+                .iter()
+                .flat_map(|next| cost(current, next).map(|c| (Some(*next), c)))
+                .collect(),
+        }
+    }
+}
+
+fn strip_virtual_start(nodes: Vec<Option<Point>>) -> Vec<Point> {
+    nodes.into_iter().flatten().collect()
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 is open ground, 9 is a wall, anything else is the cost of entering the cell.
+    fn get_test_grid() -> Grid<u8> {
+        let mut grid = Grid::<u8>::new();
+        grid.add_row(vec![0, 0, 0, 0]);
+        grid.add_row(vec![9, 9, 5, 0]);
+        grid.add_row(vec![0, 0, 0, 0]);
+        grid.add_row(vec![0, 9, 9, 9]);
+        grid
+    }
+
+    fn step_cost(grid: &Grid<u8>) -> impl Fn(&Point, &Point) -> Option<usize> + '_ {
+        |_, to| match grid.get_element(to) {
+            Some(9) | None => None,
+            Some(0) => Some(1),
+            Some(n) => Some(*n as usize),
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = get_test_grid();
+        let goal = Point { x: 0, y: 3 };
+        let path = grid
+            .bfs(&Point { x: 0, y: 0 }, |p| *p == goal, step_cost(&grid))
+            .unwrap();
+
+        // through the 5 at (2, 1), since bfs ignores costs
+        assert_eq!(7, path.steps());
+        assert_eq!(7, path.cost);
+        assert_eq!(&Point { x: 0, y: 0 }, path.start());
+        assert_eq!(&goal, path.end());
+        assert!(path.points.contains(&Point { x: 2, y: 1 }));
+
+        let wall = Point { x: 3, y: 3 };
+        assert_eq!(
+            None,
+            grid.bfs(&Point { x: 0, y: 0 }, |p| *p == wall, step_cost(&grid))
+        );
+    }
+
+    #[test]
+    fn test_path_to_self_has_no_steps() {
+        let grid = get_test_grid();
+        let start = Point { x: 0, y: 0 };
+        let path = grid.bfs(&start, |p| *p == start, step_cost(&grid)).unwrap();
+
+        assert_eq!(vec![start], path.points);
+        assert_eq!(0, path.steps());
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let grid = get_test_grid();
+        let start = Point { x: 0, y: 0 };
+        let goal = Point { x: 0, y: 3 };
+
+        // around the 5 via the right hand column
+        let path = grid
+            .dijkstra(&start, |p| *p == goal, step_cost(&grid))
+            .unwrap();
+        assert_eq!(9, path.steps());
+        assert_eq!(9, path.cost);
+        assert!(!path.points.contains(&Point { x: 2, y: 1 }));
+
+        let manhattan = |p: &Point| p.x.abs_diff(goal.x) + p.y.abs_diff(goal.y);
+        let path = grid
+            .astar(&start, |p| *p == goal, step_cost(&grid), manhattan)
+            .unwrap();
+        assert_eq!(9, path.cost);
+    }
+
+    #[test]
+    fn test_multi_source() {
+        let grid = get_test_grid();
+        let starts = [Point { x: 0, y: 0 }, Point { x: 3, y: 2 }];
+        let goal = Point { x: 0, y: 2 };
+
+        let path = grid
+            .bfs_from_any(&starts, |p| *p == goal, step_cost(&grid))
+            .unwrap();
+        assert_eq!(&Point { x: 3, y: 2 }, path.start());
+        assert_eq!(3, path.steps());
+
+        let path = grid
+            .dijkstra_from_any(&starts, |p| *p == goal, step_cost(&grid))
+            .unwrap();
+        assert_eq!(3, path.cost);
+    }
+
+    #[test]
+    fn test_cost_map() {
+        let grid = get_test_grid();
+        let costs = grid.cost_map(&[Point { x: 0, y: 0 }], step_cost(&grid));
+
+        assert_eq!(Some(0), costs.cost(&Point { x: 0, y: 0 }));
+        assert_eq!(Some(3), costs.cost(&Point { x: 3, y: 0 }));
+        assert_eq!(Some(7), costs.cost(&Point { x: 2, y: 1 }));
+        assert_eq!(Some(9), costs.cost(&Point { x: 0, y: 3 }));
+        assert_eq!(None, costs.cost(&Point { x: 0, y: 1 }));
+        assert_eq!(None, costs.cost(&Point { x: 10, y: 10 }));
+
+        let path = costs.path_to(&Point { x: 0, y: 3 }).unwrap();
+        assert_eq!(9, path.steps());
+        assert_eq!(&Point { x: 0, y: 0 }, path.start());
+        assert_eq!(None, costs.path_to(&Point { x: 1, y: 1 }));
+    }
+}
@@ -0,0 +1,149 @@
+use super::{Direction, Grid, Point};
+
+/// Iterator over the cells in a straight line from a point, not including the point itself.
+/// Ends at the edge of a bounded grid. On a grid whose topology wraps, it ends before it would return to
+/// the start point, or once every cell has been passed in every direction.
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    start: Point,
+    current: Point,
+    direction: Direction,
+    remaining: usize,
+}
+
+impl<'a, T: Clone> Iterator for Ray<'a, T> {
+    type Item = (Point, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // guards against custom topologies that loop without passing the start point
+        self.remaining = self.remaining.checked_sub(1)?;
+
+        let (next, direction) = self.grid.step(&self.current, self.direction)?;
+        if next == self.start {
+            return None;
+        }
+
+        let element = self.grid.get_element(&next)?;
+        self.current = next;
+        self.direction = direction;
+
+        Some((next, element))
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Clone> Grid<T> {
+    /// Look from `point` in `direction`. Empty if `point` is on the edge facing out, or outside the grid.
+    pub fn ray(&self, point: &Point, direction: Direction) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            start: *point,
+            current: *point,
+            direction,
+            remaining: self.elements.len() * Direction::ALL.len(),
+        }
+    }
+
+    /// The first cell from `point` in `direction` that matches `predicate`.
+    pub fn first_matching<P>(
+        &self,
+        point: &Point,
+        direction: Direction,
+        predicate: P,
+    ) -> Option<(Point, &T)>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.ray(point, direction).find(|(_, e)| predicate(e))
+    }
+
+    /// Number of cells that can be seen from `point` in `direction`. Counts up to and including the
+    /// first cell that is `blocked`, or up to the edge if nothing blocks the view.
+    pub fn count_until_blocked<P>(&self, point: &Point, direction: Direction, blocked: P) -> usize
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut count = 0;
+        for (_, element) in self.ray(point, direction) {
+            count += 1;
+            if blocked(element) {
+                break;
+            }
+        }
+        count
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_grid() -> Grid<u8> {
+        let mut grid = Grid::<u8>::new();
+        grid.add_row(vec![1, 2, 3]);
+        grid.add_row(vec![4, 5, 6]);
+        grid.add_row(vec![7, 8, 9]);
+        grid
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = get_test_grid();
+        let centre = Point { x: 1, y: 1 };
+
+        let up: Vec<(Point, &u8)> = grid.ray(&centre, Direction::Up).collect();
+        assert_eq!(vec![(Point { x: 1, y: 0 }, &2)], up);
+
+        let corner = Point { x: 0, y: 0 };
+        let values: Vec<u8> = grid
+            .ray(&corner, Direction::DownRight)
+            .map(|(_, e)| *e)
+            .collect();
+        assert_eq!(vec![5, 9], values);
+
+        let values: Vec<u8> = grid
+            .ray(&corner, Direction::Right)
+            .map(|(_, e)| *e)
+            .collect();
+        assert_eq!(vec![2, 3], values);
+
+        assert_eq!(0, grid.ray(&corner, Direction::Left).count());
+        assert_eq!(0, grid.ray(&corner, Direction::UpLeft).count());
+        assert_eq!(0, grid.ray(&Point { x: 5, y: 5 }, Direction::Up).count());
+    }
+
+    #[test]
+    fn test_first_matching() {
+        let grid = get_test_grid();
+        let start = Point { x: 0, y: 2 };
+
+        assert_eq!(
+            Some((Point { x: 2, y: 2 }, &9)),
+            grid.first_matching(&start, Direction::Right, |e| *e > 8)
+        );
+        assert_eq!(None, grid.first_matching(&start, Direction::Up, |e| *e > 8));
+    }
+
+    #[test]
+    fn test_count_until_blocked() {
+        let mut grid = Grid::<u8>::new();
+        grid.add_row(vec![2, 7, 1, 2, 3]);
+        let start = Point { x: 4, y: 0 };
+
+        assert_eq!(
+            3,
+            grid.count_until_blocked(&start, Direction::Left, |e| *e >= 3)
+        );
+        assert_eq!(
+            4,
+            grid.count_until_blocked(&start, Direction::Left, |e| *e >= 8)
+        );
+        assert_eq!(
+            0,
+            grid.count_until_blocked(&start, Direction::Right, |e| *e >= 3)
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use super::{Grid, Neighbourhood, Point};
+
+/// Summary of one connected component from `Grid::label_components`.
+///     min/max: corners of the inclusive bounding box.
+///     perimeter: number of cell sides that border a cell outside the component, or the grid edge.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Component {
+    pub label: usize,
+    pub size: usize,
+    pub min: Point,
+    pub max: Point,
+    pub perimeter: usize,
+}
+
+#[allow(dead_code)]
+impl<T: Clone> Grid<T> {
+    /// All points reachable from `start` through orthogonally adjacent cells that match `predicate`.
+    /// Empty if `start` itself does not match or is outside the grid.
+    pub fn flood_fill<P>(&self, start: &Point, predicate: P) -> HashSet<Point>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.fill_from(start, &predicate, Neighbourhood::Orthogonal)
+    }
+
+    /// Label each group of connected cells matching `predicate`. Cells in the same group share a label,
+    /// numbered from 0 in row-major order of their first cell. Cells that don't match are None.
+    pub fn label_components<P>(
+        &self,
+        predicate: P,
+        neighbourhood: Neighbourhood,
+    ) -> Grid<Option<usize>>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut labels = Grid::filled(self.columns, self.rows, None);
+
+        let mut next_label = 0;
+
+        for index in 0..self.elements.len() {
+            let point = self.index_to_point(index).expect("Index should be in grid");
+
+            if labels.elements[index].is_some() || !predicate(&self.elements[index]) {
+                continue;
+            }
+
+            for p in self.fill_from(&point, &predicate, neighbourhood) {
+                *labels.get_element_mut(&p).expect("Point should be in grid") = Some(next_label);
+            }
+            next_label += 1;
+        }
+
+        labels
+    }
+
+    fn fill_from<P>(
+        &self,
+        start: &Point,
+        predicate: &P,
+        neighbourhood: Neighbourhood,
+    ) -> HashSet<Point>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut filled = HashSet::new();
+
+        if !self.get_element(start).is_some_and(predicate) {
+            return filled;
+        }
+
+        let mut queue = VecDeque::from([*start]);
+        filled.insert(*start);
+
+        while let Some(current) = queue.pop_front() {
+            for next in self.get_neighbours(&current, neighbourhood) {
+                if !filled.contains(&next) && self.get_element(&next).is_some_and(predicate) {
+                    filled.insert(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        filled
+    }
+}
+
+#[allow(dead_code)]
+impl Grid<Option<usize>> {
+    /// Size, bounding box and perimeter of every component in a grid of labels, ordered by label.
+    pub fn component_stats(&self) -> Vec<Component> {
+        let mut components: BTreeMap<usize, Component> = BTreeMap::new();
+
+        for (index, label) in self.elements.iter().enumerate() {
+            let Some(label) = *label else {
+                continue;
+            };
+            let point = self.index_to_point(index).expect("Index should be in grid");
+            let perimeter = self.exposed_sides(&point, label);
+
+            match components.get_mut(&label) {
+                Some(c) => {
+                    c.size += 1;
+                    c.min = Point {
+                        x: c.min.x.min(point.x),
+                        y: c.min.y.min(point.y),
+                    };
+                    c.max = Point {
+                        x: c.max.x.max(point.x),
+                        y: c.max.y.max(point.y),
+                    };
+                    c.perimeter += perimeter;
+                }
+                None => {
+                    components.insert(
+                        label,
+                        Component {
+                            label,
+                            size: 1,
+                            min: point,
+                            max: point,
+                            perimeter,
+                        },
+                    );
+                }
+            }
+        }
+
+        components.into_values().collect()
+    }
+
+    fn exposed_sides(&self, point: &Point, label: usize) -> usize {
+        let same_label = self
+            .get_adjacent_points(point)
+            .iter()
+            .filter(|p| self.get_element(p) == Some(&Some(label)))
+            .count();
+
+        4 - same_label
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_grid() -> Grid<char> {
+        let mut grid = Grid::<char>::new();
+        grid.add_row("##..#".chars().collect());
+        grid.add_row("#...#".chars().collect());
+        grid.add_row("..#..".chars().collect());
+        grid.add_row(".#.##".chars().collect());
+        grid
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = get_test_grid();
+
+        let filled = grid.flood_fill(&Point { x: 0, y: 0 }, |c| *c == '#');
+        let mut filled: Vec<Point> = filled.into_iter().collect();
+        filled.sort();
+        assert_eq!(
+            vec![
+                Point { x: 0, y: 0 },
+                Point { x: 0, y: 1 },
+                Point { x: 1, y: 0 }
+            ],
+            filled
+        );
+
+        let filled = grid.flood_fill(&Point { x: 2, y: 0 }, |c| *c == '.');
+        // (2, 3) is walled in
+        assert_eq!(10, filled.len());
+
+        assert!(grid
+            .flood_fill(&Point { x: 2, y: 0 }, |c| *c == '#')
+            .is_empty());
+        assert!(grid
+            .flood_fill(&Point { x: 9, y: 9 }, |c| *c == '#')
+            .is_empty());
+    }
+
+    #[test]
+    fn test_label_components() {
+        let grid = get_test_grid();
+
+        let labels = grid.label_components(|c| *c == '#', Neighbourhood::Orthogonal);
+        assert_eq!(
+            vec![Some(0), Some(0), None, None, Some(1)],
+            labels.get_row(0).unwrap()
+        );
+        assert_eq!(
+            vec![None, Some(3), None, Some(4), Some(4)],
+            labels.get_row(3).unwrap()
+        );
+        assert_eq!(Some(&Some(2)), labels.get_element(&Point { x: 2, y: 2 }));
+
+        // diagonals join the middle cell to the bottom left and bottom right groups
+        let labels = grid.label_components(|c| *c == '#', Neighbourhood::All);
+        assert_eq!(
+            vec![None, Some(2), None, Some(2), Some(2)],
+            labels.get_row(3).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_component_stats() {
+        let grid = get_test_grid();
+        let stats = grid
+            .label_components(|c| *c == '#', Neighbourhood::Orthogonal)
+            .component_stats();
+
+        assert_eq!(5, stats.len());
+        assert_eq!(
+            Component {
+                label: 0,
+                size: 3,
+                min: Point { x: 0, y: 0 },
+                max: Point { x: 1, y: 1 },
+                perimeter: 8,
+            },
+            stats[0]
+        );
+        assert_eq!(
+            Component {
+                label: 4,
+                size: 2,
+                min: Point { x: 3, y: 3 },
+                max: Point { x: 4, y: 3 },
+                perimeter: 6,
+            },
+            stats[4]
+        );
+
+        // labels needn't come from `label_components`
+        let mut labels = Grid::filled(2, 1, None);
+        labels.elements = vec![Some(usize::MAX), Some(1 << 40)];
+        let stats = labels.component_stats();
+        assert_eq!(
+            vec![1 << 40, usize::MAX],
+            stats.iter().map(|c| c.label).collect::<Vec<_>>()
+        );
+    }
+}
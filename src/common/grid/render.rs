@@ -0,0 +1,226 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fs;
+
+use super::{Grid, Point};
+
+/// Plain Netpbm files should not have lines longer than this.
+const NETPBM_LINE_LIMIT: usize = 70;
+const ANSI_RESET: &str = "\x1b[0m";
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// A set of points to highlight with a background colour when rendering to the terminal,
+/// e.g. the points of a `Path`.
+#[derive(Debug)]
+pub struct Overlay {
+    points: HashSet<Point>,
+    colour: Rgb,
+}
+
+#[allow(dead_code)]
+impl Overlay {
+    pub fn new<I: IntoIterator<Item = Point>>(points: I, colour: Rgb) -> Self {
+        Overlay {
+            points: points.into_iter().collect(),
+            colour,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Clone> Grid<T> {
+    /// Render as a plain (P2) greyscale PGM image, with `shade` giving the grey level of each element.
+    pub fn to_pgm<F>(&self, shade: F) -> String
+    where
+        F: Fn(&T) -> u8,
+    {
+        let values = self.elements.iter().map(|e| vec![shade(e)]);
+        self.to_plain_netpbm("P2", values)
+    }
+
+    /// Render as a plain (P3) colour PPM image, with `colour` giving the colour of each element.
+    pub fn to_ppm<F>(&self, colour: F) -> String
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let values = self.elements.iter().map(|e| {
+            let Rgb(r, g, b) = colour(e);
+            vec![r, g, b]
+        });
+        self.to_plain_netpbm("P3", values)
+    }
+
+    pub fn write_pgm<F>(&self, filename: &str, shade: F) -> Result<(), std::io::Error>
+    where
+        F: Fn(&T) -> u8,
+    {
+        fs::write(filename, self.to_pgm(shade))
+    }
+
+    pub fn write_ppm<F>(&self, filename: &str, colour: F) -> Result<(), std::io::Error>
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        fs::write(filename, self.to_ppm(colour))
+    }
+
+    fn to_plain_netpbm<I>(&self, magic: &str, pixels: I) -> String
+    where
+        I: Iterator<Item = Vec<u8>>,
+    {
+        let mut out = format!("{magic}\n{} {}\n255\n", self.columns, self.rows);
+        let mut line = String::new();
+
+        for value in pixels.flatten() {
+            let value = value.to_string();
+
+            if !line.is_empty() && line.len() + 1 + value.len() > NETPBM_LINE_LIMIT {
+                out.push_str(&line);
+                out.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&value);
+        }
+
+        if !line.is_empty() {
+            out.push_str(&line);
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Clone + Display> Grid<T> {
+    /// Render for the terminal. Elements are padded to the same width and separated by a space.
+    /// `colour` sets the text colour of each element, and the points of each overlay get its colour as a
+    /// background. Where overlays share a point, the last one wins.
+    pub fn render_ansi<F>(&self, colour: F, overlays: &[Overlay]) -> String
+    where
+        F: Fn(&T) -> Option<Rgb>,
+    {
+        let width = self
+            .elements
+            .iter()
+            .map(|e| e.to_string().chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut out = String::new();
+
+        for (index, element) in self.elements.iter().enumerate() {
+            let point = self.index_to_point(index).expect("Index should be in grid");
+
+            if point.x != 0 {
+                out.push(' ');
+            }
+
+            let background = overlays.iter().rev().find(|o| o.points.contains(&point));
+            let foreground = colour(element);
+
+            if let Some(Rgb(r, g, b)) = foreground {
+                out.push_str(&format!("\x1b[38;2;{r};{g};{b}m"));
+            }
+            if let Some(Overlay {
+                colour: Rgb(r, g, b),
+                ..
+            }) = background
+            {
+                out.push_str(&format!("\x1b[48;2;{r};{g};{b}m"));
+            }
+
+            out.push_str(&format!("{:>width$}", element.to_string()));
+
+            if foreground.is_some() || background.is_some() {
+                out.push_str(ANSI_RESET);
+            }
+            if point.x == self.columns - 1 {
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+
+    pub fn print_ansi<F>(&self, colour: F, overlays: &[Overlay])
+    where
+        F: Fn(&T) -> Option<Rgb>,
+    {
+        print!("{}", self.render_ansi(colour, overlays));
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_grid() -> Grid<u8> {
+        let mut grid = Grid::<u8>::new();
+        grid.add_row(vec![0, 5, 10]);
+        grid.add_row(vec![9, 1, 2]);
+        grid
+    }
+
+    #[test]
+    fn test_to_pgm() {
+        let grid = get_test_grid();
+        let pgm = grid.to_pgm(|e| e * 25);
+        assert_eq!("P2\n3 2\n255\n0 125 250 225 25 50\n", pgm);
+    }
+
+    #[test]
+    fn test_to_ppm() {
+        let grid = get_test_grid();
+        let ppm = grid.to_ppm(|e| if *e > 4 { Rgb(255, 0, 0) } else { Rgb(0, 0, 0) });
+
+        assert_eq!(
+            "P3\n3 2\n255\n0 0 0 255 0 0 255 0 0 255 0 0 0 0 0 0 0 0\n",
+            ppm
+        );
+    }
+
+    #[test]
+    fn test_netpbm_line_limit() {
+        let mut grid = Grid::<u8>::new();
+        grid.add_row(vec![255; 30]);
+
+        let pgm = grid.to_pgm(|e| *e);
+        let lines: Vec<&str> = pgm.lines().skip(3).collect();
+        assert_eq!(2, lines.len());
+        assert_eq!(17 * 4 - 1, lines[0].len());
+        assert_eq!(13 * 4 - 1, lines[1].len());
+    }
+
+    #[test]
+    fn test_write_pgm() {
+        let grid = get_test_grid();
+        let filename = std::env::temp_dir().join("chrustmas_test_write.pgm");
+        let filename = filename.to_str().unwrap();
+
+        grid.write_pgm(filename, |e| *e).unwrap();
+        assert_eq!(grid.to_pgm(|e| *e), fs::read_to_string(filename).unwrap());
+        fs::remove_file(filename).unwrap();
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let grid = get_test_grid();
+
+        assert_eq!(" 0  5 10\n 9  1  2\n", grid.render_ansi(|_| None, &[]));
+
+        let path = Overlay::new(vec![Point { x: 1, y: 1 }], Rgb(0, 0, 255));
+        let rendered = grid.render_ansi(|e| (*e == 10).then_some(Rgb(0, 255, 0)), &[path]);
+        assert_eq!(
+            " 0  5 \x1b[38;2;0;255;0m10\x1b[0m\n 9 \x1b[48;2;0;0;255m 1\x1b[0m  2\n",
+            rendered
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use super::{Grid, Neighbourhood, Point};
+
+/// SparseGrid is an unbounded 2-dimensional grid with signed coordinates. Only cells that have been set
+/// are stored, so it can grow in any direction, including into negative coordinates.
+///
+/// The bounding box of all set cells is tracked as cells are added and removed. As with `Grid`, `y` is the row
+/// and increases downwards when rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<SparsePoint, T>,
+    bounds: Option<Bounds>,
+}
+
+/// Signed point used to index a `SparseGrid`.
+pub type SparsePoint = Point<i64>;
+
+/// Inclusive rectangle covering every set cell of a `SparseGrid`.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Bounds {
+    pub min: SparsePoint,
+    pub max: SparsePoint,
+}
+
+#[allow(dead_code)]
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    pub fn contains(&self, point: &SparsePoint) -> bool {
+        self.cells.contains_key(point)
+    }
+
+    pub fn get(&self, point: &SparsePoint) -> Option<&T> {
+        self.cells.get(point)
+    }
+
+    pub fn get_mut(&mut self, point: &SparsePoint) -> Option<&mut T> {
+        self.cells.get_mut(point)
+    }
+
+    /// Set the value at `point`, returning the previous value if there was one.
+    pub fn set(&mut self, point: SparsePoint, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some(bounds) => bounds.extend(&point),
+            None => Bounds {
+                min: point,
+                max: point,
+            },
+        });
+
+        self.cells.insert(point, value)
+    }
+
+    /// Remove the value at `point`. The bounding box shrinks if the point was on its edge.
+    pub fn remove(&mut self, point: &SparsePoint) -> Option<T> {
+        let removed = self.cells.remove(point);
+
+        if removed.is_some() && self.bounds.is_some_and(|b| b.is_on_edge(point)) {
+            self.recalculate_bounds();
+        }
+
+        removed
+    }
+
+    /// Remove every cell.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds = None;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&SparsePoint, &T)> {
+        self.cells.iter()
+    }
+
+    /// Get the points around `point` for the given neighbourhood, whether or not they are set.
+    pub fn get_neighbours(
+        &self,
+        point: &SparsePoint,
+        neighbourhood: Neighbourhood,
+    ) -> Vec<SparsePoint> {
+        neighbourhood
+            .directions()
+            .iter()
+            .map(|d| {
+                let (i, j) = d.offset();
+                *point + Point::new(i as i64, j as i64)
+            })
+            .collect()
+    }
+
+    /// Get the set points, and their values, around `point` for the given neighbourhood.
+    pub fn get_set_neighbours(
+        &self,
+        point: &SparsePoint,
+        neighbourhood: Neighbourhood,
+    ) -> Vec<(SparsePoint, &T)> {
+        self.get_neighbours(point, neighbourhood)
+            .into_iter()
+            .flat_map(|p| self.get(&p).map(|v| (p, v)))
+            .collect()
+    }
+
+    fn recalculate_bounds(&mut self) {
+        self.bounds = self.cells.keys().fold(None, |bounds, p| {
+            Some(match bounds {
+                Some(b) => b.extend(p),
+                None => Bounds { min: *p, max: *p },
+            })
+        });
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Display> SparseGrid<T> {
+    /// Render the cells within the bounding box as text, one line per row. Cells that are not set
+    /// are drawn with `default`.
+    pub fn render(&self, default: char) -> String {
+        let mut out = String::new();
+
+        if let Some(bounds) = self.bounds {
+            for y in bounds.min.y..=bounds.max.y {
+                for x in bounds.min.x..=bounds.max.x {
+                    match self.get(&SparsePoint { x, y }) {
+                        Some(v) => out.push_str(&v.to_string()),
+                        None => out.push(default),
+                    }
+                }
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Clone> SparseGrid<T> {
+    /// Convert to a dense `Grid` covering the bounding box. The top-left of the bounding box becomes (0, 0),
+    /// and cells that are not set are filled with `default`.
+    pub fn to_grid(&self, default: T) -> Grid<T> {
+        let mut grid = Grid::new();
+
+        if let Some(bounds) = self.bounds {
+            for y in bounds.min.y..=bounds.max.y {
+                let row = (bounds.min.x..=bounds.max.x)
+                    .map(|x| {
+                        self.get(&SparsePoint { x, y })
+                            .cloned()
+                            .unwrap_or_else(|| default.clone())
+                    })
+                    .collect();
+                grid.add_row(row);
+            }
+        }
+
+        grid
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> From<&Grid<T>> for SparseGrid<T> {
+    fn from(grid: &Grid<T>) -> Self {
+        let mut sparse = SparseGrid::new();
+
+        for (index, value) in grid.elements.iter().enumerate() {
+            let point = grid.index_to_point(index).expect("Index should be in grid");
+            let point = point.try_into().expect("Grid index should fit in i64");
+            sparse.set(point, value.clone());
+        }
+
+        sparse
+    }
+}
+
+#[allow(dead_code)]
+impl Bounds {
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    pub fn contains(&self, point: &SparsePoint) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    fn extend(&self, point: &SparsePoint) -> Self {
+        Bounds {
+            min: self.min.min_each(point),
+            max: self.max.max_each(point),
+        }
+    }
+
+    fn is_on_edge(&self, point: &SparsePoint) -> bool {
+        point.x == self.min.x
+            || point.x == self.max.x
+            || point.y == self.min.y
+            || point.y == self.max.y
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: i64, y: i64) -> SparsePoint {
+        SparsePoint { x, y }
+    }
+
+    #[test]
+    fn test_set_get_remove() {
+        let mut grid = SparseGrid::<char>::new();
+        assert!(grid.is_empty());
+        assert_eq!(None, grid.bounds());
+
+        assert_eq!(None, grid.set(p(-2, 3), 'a'));
+        assert_eq!(Some('a'), grid.set(p(-2, 3), 'b'));
+        grid.set(p(4, -1), 'c');
+
+        assert_eq!(2, grid.len());
+        assert_eq!(Some(&'b'), grid.get(&p(-2, 3)));
+        assert_eq!(None, grid.get(&p(0, 0)));
+
+        assert_eq!(Some('c'), grid.remove(&p(4, -1)));
+        assert_eq!(None, grid.remove(&p(4, -1)));
+        assert_eq!(1, grid.len());
+    }
+
+    #[test]
+    fn test_bounds_tracking() {
+        let mut grid = SparseGrid::<char>::new();
+        grid.set(p(-2, 3), '#');
+        grid.set(p(4, -1), '#');
+        grid.set(p(0, 0), '#');
+
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(p(-2, -1), bounds.min);
+        assert_eq!(p(4, 3), bounds.max);
+        assert_eq!(7, bounds.width());
+        assert_eq!(5, bounds.height());
+
+        grid.remove(&p(4, -1));
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(p(-2, 0), bounds.min);
+        assert_eq!(p(0, 3), bounds.max);
+
+        grid.remove(&p(-2, 3));
+        grid.remove(&p(0, 0));
+        assert_eq!(None, grid.bounds());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let mut grid = SparseGrid::<u8>::new();
+        grid.set(p(0, -1), 1);
+        grid.set(p(1, 1), 2);
+
+        let neighbours = grid.get_neighbours(&p(0, 0), Neighbourhood::Orthogonal);
+        assert_eq!(vec![p(-1, 0), p(0, -1), p(0, 1), p(1, 0)], neighbours);
+        assert_eq!(8, grid.get_neighbours(&p(0, 0), Neighbourhood::All).len());
+
+        let set = grid.get_set_neighbours(&p(0, 0), Neighbourhood::Orthogonal);
+        assert_eq!(vec![(p(0, -1), &1)], set);
+        let set = grid.get_set_neighbours(&p(0, 0), Neighbourhood::Diagonal);
+        assert_eq!(vec![(p(1, 1), &2)], set);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut grid = SparseGrid::<char>::new();
+        grid.set(p(-1, -1), '#');
+        grid.set(p(1, 0), '#');
+
+        assert_eq!("#..\n..#\n", grid.render('.'));
+        assert_eq!("", SparseGrid::<char>::new().render('.'));
+    }
+
+    #[test]
+    fn test_grid_conversion() {
+        let mut dense = Grid::<u8>::new();
+        dense.add_row(vec![1, 2, 3]);
+        dense.add_row(vec![4, 5, 6]);
+
+        let mut sparse = SparseGrid::from(&dense);
+        assert_eq!(6, sparse.len());
+        assert_eq!(Some(&6), sparse.get(&p(2, 1)));
+
+        sparse.remove(&p(0, 0));
+        sparse.set(p(3, -1), 9);
+
+        let mut expected = Grid::<u8>::new();
+        expected.add_row(vec![0, 0, 0, 9]);
+        expected.add_row(vec![0, 2, 3, 0]);
+        expected.add_row(vec![4, 5, 6, 0]);
+        assert_eq!(expected, sparse.to_grid(0));
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+use std::sync::Arc;
+
+use super::{Direction, Grid, Point};
+
+/// Where a move off the edge of the grid ends up. Given the point on the edge and the direction of the move,
+/// returns the point and direction to continue with, or None if the move is blocked.
+pub type EdgeMap = Arc<dyn Fn(&Point, Direction) -> Option<(Point, Direction)> + Send + Sync>;
+
+/// How the edges of a `Grid` join up.
+///     Bounded: the edges are walls. This is the default.
+///     Toroidal: leaving one edge re-enters on the opposite edge, moving the same way.
+///     Custom: leaving an edge is handled by the given `EdgeMap`, which may also change direction.
+#[derive(Clone)]
+#[allow(dead_code)]
+pub enum Topology {
+    Bounded,
+    Toroidal,
+    Custom(EdgeMap),
+}
+
+/// A position and facing on a grid, which moves according to the grid's topology.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Hash)]
+pub struct Walker {
+    pub position: Point,
+    pub direction: Direction,
+}
+
+#[allow(dead_code)]
+impl<T: Clone> Grid<T> {
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Take one step from `point` in `direction`. Returns the point reached and the direction now faced,
+    /// which only changes if a custom topology turns us at an edge. None if the move is blocked, or
+    /// `point` is outside the grid.
+    pub fn step(&self, point: &Point, direction: Direction) -> Option<(Point, Direction)> {
+        if point.x >= self.columns || point.y >= self.rows {
+            return None;
+        }
+
+        let (i, j) = direction.offset();
+        let x = point.x as isize + i;
+        let y = point.y as isize + j;
+
+        if (0..self.columns as isize).contains(&x) && (0..self.rows as isize).contains(&y) {
+            let next = Point {
+                x: x as usize,
+                y: y as usize,
+            };
+            return Some((next, direction));
+        }
+
+        let (next, direction) = match &self.topology {
+            Topology::Bounded => return None,
+            Topology::Toroidal => {
+                let next = Point {
+                    x: x.rem_euclid(self.columns as isize) as usize,
+                    y: y.rem_euclid(self.rows as isize) as usize,
+                };
+                (next, direction)
+            }
+            Topology::Custom(edge_map) => edge_map(point, direction)?,
+        };
+
+        self.get_element(&next).map(|_| (next, direction))
+    }
+}
+
+impl fmt::Debug for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topology::Bounded => write!(f, "Bounded"),
+            Topology::Toroidal => write!(f, "Toroidal"),
+            Topology::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Walker {
+    pub fn new(position: Point, direction: Direction) -> Self {
+        Walker {
+            position,
+            direction,
+        }
+    }
+
+    pub fn turn_left(&mut self) {
+        self.direction = self.direction.turn_left();
+    }
+
+    pub fn turn_right(&mut self) {
+        self.direction = self.direction.turn_right();
+    }
+
+    /// Move one step forward. Returns false, without moving, if the grid's topology blocks the move.
+    pub fn step<T: Clone>(&mut self, grid: &Grid<T>) -> bool {
+        match grid.step(&self.position, self.direction) {
+            Some((position, direction)) => {
+                self.position = position;
+                self.direction = direction;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move forward up to `steps` times, stopping early at the edge of a bounded grid or in front of a
+    /// `blocked` cell. Returns the number of steps taken.
+    pub fn walk<T, P>(&mut self, grid: &Grid<T>, steps: usize, blocked: P) -> usize
+    where
+        T: Clone,
+        P: Fn(&T) -> bool,
+    {
+        for taken in 0..steps {
+            match grid.step(&self.position, self.direction) {
+                Some((position, direction))
+                    if !grid.get_element(&position).is_some_and(&blocked) =>
+                {
+                    self.position = position;
+                    self.direction = direction;
+                }
+                _ => return taken,
+            }
+        }
+        steps
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::grid::Neighbourhood;
+
+    fn get_test_grid() -> Grid<u8> {
+        let mut grid = Grid::<u8>::new();
+        grid.add_row(vec![0, 1, 2, 3]);
+        grid.add_row(vec![4, 5, 6, 7]);
+        grid.add_row(vec![8, 9, 10, 11]);
+        grid
+    }
+
+    #[test]
+    fn test_bounded_step() {
+        let grid = get_test_grid();
+        let corner = Point { x: 3, y: 0 };
+
+        assert_eq!(None, grid.step(&corner, Direction::Right));
+        assert_eq!(None, grid.step(&corner, Direction::Up));
+        assert_eq!(
+            Some((Point { x: 2, y: 1 }, Direction::DownLeft)),
+            grid.step(&corner, Direction::DownLeft)
+        );
+        assert_eq!(None, grid.step(&Point { x: 4, y: 0 }, Direction::Left));
+    }
+
+    #[test]
+    fn test_toroidal_step() {
+        let grid = get_test_grid().with_topology(Topology::Toroidal);
+        let corner = Point { x: 3, y: 0 };
+
+        assert_eq!(
+            Some((Point { x: 0, y: 0 }, Direction::Right)),
+            grid.step(&corner, Direction::Right)
+        );
+        assert_eq!(
+            Some((Point { x: 3, y: 2 }, Direction::Up)),
+            grid.step(&corner, Direction::Up)
+        );
+        assert_eq!(
+            Some((Point { x: 0, y: 2 }, Direction::UpRight)),
+            grid.step(&corner, Direction::UpRight)
+        );
+    }
+
+    #[test]
+    fn test_toroidal_neighbours() {
+        let grid = get_test_grid().with_topology(Topology::Toroidal);
+
+        let mut neighbours = grid.get_adjacent_points(&Point { x: 0, y: 0 });
+        neighbours.sort();
+        assert_eq!(
+            vec![
+                Point { x: 0, y: 1 },
+                Point { x: 0, y: 2 },
+                Point { x: 1, y: 0 },
+                Point { x: 3, y: 0 },
+            ],
+            neighbours
+        );
+        assert_eq!(
+            8,
+            grid.get_neighbours(&Point { x: 0, y: 0 }, Neighbourhood::All)
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_toroidal_ray_stops_before_repeating() {
+        let grid = get_test_grid().with_topology(Topology::Toroidal);
+
+        let values: Vec<u8> = grid
+            .ray(&Point { x: 1, y: 1 }, Direction::Right)
+            .map(|(_, e)| *e)
+            .collect();
+        assert_eq!(vec![6, 7, 4], values);
+
+        // 3 rows and 4 columns: the diagonal visits all 12 cells before returning to the start
+        assert_eq!(
+            11,
+            grid.ray(&Point { x: 0, y: 0 }, Direction::DownRight)
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_custom_topology() {
+        // leaving the right edge drops down a row and comes back facing left
+        let edge_map: EdgeMap = Arc::new(|p: &Point, d: Direction| match d {
+            Direction::Right => Some((Point { x: p.x, y: p.y + 1 }, Direction::Left)),
+            _ => None,
+        });
+        let grid = get_test_grid().with_topology(Topology::Custom(edge_map));
+
+        assert_eq!(
+            Some((Point { x: 3, y: 1 }, Direction::Left)),
+            grid.step(&Point { x: 3, y: 0 }, Direction::Right)
+        );
+        assert_eq!(None, grid.step(&Point { x: 3, y: 2 }, Direction::Right));
+        assert_eq!(None, grid.step(&Point { x: 0, y: 0 }, Direction::Left));
+
+        let values: Vec<u8> = grid
+            .ray(&Point { x: 2, y: 0 }, Direction::Right)
+            .map(|(_, e)| *e)
+            .collect();
+        assert_eq!(vec![3, 7, 6, 5, 4], values);
+    }
+
+    #[test]
+    fn test_walker() {
+        let grid = get_test_grid().with_topology(Topology::Toroidal);
+        let mut walker = Walker::new(Point { x: 0, y: 0 }, Direction::Up);
+
+        assert!(walker.step(&grid));
+        assert_eq!(Point { x: 0, y: 2 }, walker.position);
+
+        walker.turn_right();
+        assert_eq!(2, walker.walk(&grid, 5, |e| *e == 11));
+        assert_eq!(Point { x: 2, y: 2 }, walker.position);
+
+        walker.turn_left();
+        walker.turn_left();
+        assert_eq!(5, walker.walk(&grid, 5, |_| false));
+        assert_eq!(Point { x: 1, y: 2 }, walker.position);
+        assert_eq!(Direction::Left, walker.direction);
+
+        let bounded = get_test_grid();
+        let mut walker = Walker::new(Point { x: 0, y: 0 }, Direction::Up);
+        assert!(!walker.step(&bounded));
+        assert_eq!(Point { x: 0, y: 0 }, walker.position);
+    }
+}
@@ -0,0 +1,153 @@
+use super::{Grid, Point};
+
+/// Element-wise operations over a whole grid. Elements are visited in row-major order, so where a
+/// function returns the first match it's the one nearest the top-left corner, along rows first.
+#[allow(dead_code)]
+impl<T: Clone> Grid<T> {
+    /// A grid of the same size and topology with `f` applied to every element.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        self.with_elements(self.elements.iter().map(f).collect())
+    }
+
+    /// As `map`, but `f` is also given the point of each element.
+    pub fn map_with_point<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: Fn(&Point, &T) -> U,
+    {
+        self.with_elements(
+            self.elements
+                .iter()
+                .enumerate()
+                .map(|(index, e)| f(&self.point_at(index), e))
+                .collect(),
+        )
+    }
+
+    /// Combine each element with the element at the same point of `other`. None if the grids are not
+    /// the same size.
+    pub fn zip_with<U, V, F>(&self, other: &Grid<U>, f: F) -> Option<Grid<V>>
+    where
+        F: Fn(&T, &U) -> V,
+    {
+        if self.columns != other.columns || self.rows != other.rows {
+            return None;
+        }
+
+        Some(
+            self.with_elements(
+                self.elements
+                    .iter()
+                    .zip(other.elements.iter())
+                    .map(|(a, b)| f(a, b))
+                    .collect(),
+            ),
+        )
+    }
+
+    /// Number of elements matching `predicate`.
+    pub fn count<P>(&self, predicate: P) -> usize
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.elements.iter().filter(|e| predicate(e)).count()
+    }
+
+    /// Point of the first element matching `predicate`.
+    pub fn find<P>(&self, predicate: P) -> Option<Point>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.elements
+            .iter()
+            .position(predicate)
+            .map(|index| self.point_at(index))
+    }
+
+    /// Points of every element matching `predicate`.
+    pub fn positions<P>(&self, predicate: P) -> Vec<Point>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.elements
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| predicate(e))
+            .map(|(index, _)| self.point_at(index))
+            .collect()
+    }
+
+    fn with_elements<U>(&self, elements: Vec<U>) -> Grid<U> {
+        Grid {
+            elements,
+            columns: self.columns,
+            rows: self.rows,
+            topology: self.topology.clone(),
+        }
+    }
+
+    fn point_at(&self, index: usize) -> Point {
+        self.index_to_point(index).expect("Index should be in grid")
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_grid() -> Grid<u8> {
+        let mut grid = Grid::<u8>::new();
+        grid.add_row(vec![0, 0, 1, 5]);
+        grid.add_row(vec![1, 3, 1, 7]);
+        grid.add_row(vec![8, 7, 1, 10]);
+        grid
+    }
+
+    #[test]
+    fn test_map() {
+        let grid = get_test_grid();
+
+        let odd = grid.map(|e| e % 2 == 1);
+        assert_eq!((4, 3), (odd.columns, odd.rows));
+        assert_eq!(vec![true, true, true, true], odd.get_row(1).unwrap());
+
+        let sums = grid.map_with_point(|p, e| p.x + p.y + *e as usize);
+        assert_eq!(vec![2, 5, 4, 11], sums.get_row(1).unwrap());
+    }
+
+    #[test]
+    fn test_zip_with() {
+        let grid = get_test_grid();
+        let doubled = grid.map(|e| *e as u16 * 2);
+
+        let zipped = grid.zip_with(&doubled, |a, b| *a as u16 + b).unwrap();
+        assert_eq!(vec![24, 21, 3, 30], zipped.get_row(2).unwrap());
+
+        assert!(grid
+            .zip_with(&Grid::filled(3, 4, 0), |a, b| a + b)
+            .is_none());
+    }
+
+    #[test]
+    fn test_count_find_and_positions() {
+        let grid = get_test_grid();
+
+        assert_eq!(4, grid.count(|e| *e == 1));
+        assert_eq!(Some(Point { x: 2, y: 0 }), grid.find(|e| *e == 1));
+        assert_eq!(None, grid.find(|e| *e > 10));
+        assert_eq!(
+            vec![
+                Point { x: 3, y: 1 },
+                Point { x: 0, y: 2 },
+                Point { x: 1, y: 2 },
+                Point { x: 3, y: 2 }
+            ],
+            grid.positions(|e| *e > 5)
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use std::fmt::Display;
+
+use super::{Grid, Neighbourhood, Point};
+
+/// A borrowed rectangle of a `Grid`. Points passed to and returned from a view are relative to its top-left
+/// corner, which is `origin` in the underlying grid.
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    rect: Rect,
+}
+
+/// As `GridView`, but the elements of the rectangle can be changed.
+pub struct GridViewMut<'a, T> {
+    grid: &'a mut Grid<T>,
+    rect: Rect,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+struct Rect {
+    origin: Point,
+    columns: usize,
+    rows: usize,
+}
+
+#[allow(dead_code)]
+impl<T: Clone> Grid<T> {
+    /// View the rectangle with top-left corner `origin`. None if the rectangle doesn't fit in the grid.
+    pub fn view(&self, origin: Point, columns: usize, rows: usize) -> Option<GridView<'_, T>> {
+        let rect = self.rect(origin, columns, rows)?;
+        Some(GridView { grid: self, rect })
+    }
+
+    pub fn view_mut(
+        &mut self,
+        origin: Point,
+        columns: usize,
+        rows: usize,
+    ) -> Option<GridViewMut<'_, T>> {
+        let rect = self.rect(origin, columns, rows)?;
+        Some(GridViewMut { grid: self, rect })
+    }
+
+    /// View of the grid without first and last rows and columns. Like `get_inner_grid`, without copying.
+    pub fn inner_view(&self) -> Option<GridView<'_, T>> {
+        self.view(
+            Point { x: 1, y: 1 },
+            self.columns.checked_sub(2)?,
+            self.rows.checked_sub(2)?,
+        )
+    }
+
+    /// Every `columns` by `rows` rectangle in the grid, moving along each row of the grid and then down.
+    pub fn windows(&self, columns: usize, rows: usize) -> impl Iterator<Item = GridView<'_, T>> {
+        let last_x = (self.columns + 1).saturating_sub(columns);
+        let last_y = (self.rows + 1).saturating_sub(rows);
+
+        (0..last_y)
+            .flat_map(move |y| (0..last_x).map(move |x| Point { x, y }))
+            .flat_map(move |origin| self.view(origin, columns, rows))
+    }
+
+    fn rect(&self, origin: Point, columns: usize, rows: usize) -> Option<Rect> {
+        if columns == 0 || rows == 0 {
+            return None;
+        }
+        let fits = |start: usize, length: usize, size: usize| {
+            start.checked_add(length).is_some_and(|end| end <= size)
+        };
+        if !fits(origin.x, columns, self.columns) || !fits(origin.y, rows, self.rows) {
+            return None;
+        }
+
+        Some(Rect {
+            origin,
+            columns,
+            rows,
+        })
+    }
+}
+
+#[allow(dead_code)]
+impl<'a, T: Clone> GridView<'a, T> {
+    pub fn columns(&self) -> usize {
+        self.rect.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rect.rows
+    }
+
+    pub fn origin(&self) -> Point {
+        self.rect.origin
+    }
+
+    /// Convert a point in the view to the same point in the underlying grid.
+    pub fn to_grid_point(&self, point: &Point) -> Option<Point> {
+        self.rect.grid_point(point)
+    }
+
+    pub fn get_element(&self, point: &Point) -> Option<&'a T> {
+        self.grid.get_element(&self.rect.grid_point(point)?)
+    }
+
+    pub fn get_row(&self, row: usize) -> Option<Vec<T>> {
+        self.rect.row_points(row).map(|points| {
+            points
+                .iter()
+                .flat_map(|p| self.grid.get_element(p).cloned())
+                .collect()
+        })
+    }
+
+    pub fn get_column(&self, column: usize) -> Option<Vec<T>> {
+        self.rect.column_points(column).map(|points| {
+            points
+                .iter()
+                .flat_map(|p| self.grid.get_element(p).cloned())
+                .collect()
+        })
+    }
+
+    pub fn get_adjacent_points(&self, point: &Point) -> Vec<Point> {
+        self.get_neighbours(point, Neighbourhood::Orthogonal)
+    }
+
+    /// Get the points around `point` for the given neighbourhood. Views don't wrap, whatever the topology
+    /// of the grid, so only points inside the view are returned.
+    pub fn get_neighbours(&self, point: &Point, neighbourhood: Neighbourhood) -> Vec<Point> {
+        self.rect.get_neighbours(point, neighbourhood)
+    }
+
+    pub fn is_edge_node(&self, point: &Point) -> bool {
+        self.rect.is_edge_node(point)
+    }
+
+    /// Every element of the view with its point, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &'a T)> + '_ {
+        self.rect.points().map(|p| {
+            let grid_point = self.rect.grid_point(&p).expect("Point should be in view");
+            (
+                p,
+                self.grid
+                    .get_element(&grid_point)
+                    .expect("View is inside grid"),
+            )
+        })
+    }
+
+    /// Copy the view into a grid of its own.
+    pub fn to_grid(&self) -> Grid<T> {
+        let mut grid = Grid::new();
+        (0..self.rect.rows).for_each(|row| grid.add_row(self.get_row(row).unwrap()));
+        grid
+    }
+}
+
+#[allow(dead_code)]
+impl<'a, T: Clone> GridViewMut<'a, T> {
+    pub fn columns(&self) -> usize {
+        self.rect.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rect.rows
+    }
+
+    pub fn origin(&self) -> Point {
+        self.rect.origin
+    }
+
+    pub fn to_grid_point(&self, point: &Point) -> Option<Point> {
+        self.rect.grid_point(point)
+    }
+
+    pub fn get_element(&self, point: &Point) -> Option<&T> {
+        self.grid.get_element(&self.rect.grid_point(point)?)
+    }
+
+    pub fn get_element_mut(&mut self, point: &Point) -> Option<&mut T> {
+        self.grid.get_element_mut(&self.rect.grid_point(point)?)
+    }
+
+    pub fn get_row(&self, row: usize) -> Option<Vec<T>> {
+        self.as_view().get_row(row)
+    }
+
+    pub fn get_column(&self, column: usize) -> Option<Vec<T>> {
+        self.as_view().get_column(column)
+    }
+
+    pub fn get_adjacent_points(&self, point: &Point) -> Vec<Point> {
+        self.get_neighbours(point, Neighbourhood::Orthogonal)
+    }
+
+    pub fn get_neighbours(&self, point: &Point, neighbourhood: Neighbourhood) -> Vec<Point> {
+        self.rect.get_neighbours(point, neighbourhood)
+    }
+
+    pub fn is_edge_node(&self, point: &Point) -> bool {
+        self.rect.is_edge_node(point)
+    }
+
+    /// Set every element of the view to `value`.
+    pub fn fill(&mut self, value: T) {
+        for point in self.rect.points() {
+            *self
+                .get_element_mut(&point)
+                .expect("Point should be in view") = value.clone();
+        }
+    }
+
+    pub fn as_view(&self) -> GridView<'_, T> {
+        GridView {
+            grid: self.grid,
+            rect: self.rect,
+        }
+    }
+}
+
+impl Rect {
+    fn grid_point(&self, point: &Point) -> Option<Point> {
+        if point.x >= self.columns || point.y >= self.rows {
+            return None;
+        }
+
+        Some(self.origin + *point)
+    }
+
+    fn row_points(&self, row: usize) -> Option<Vec<Point>> {
+        (0..self.columns)
+            .map(|x| self.grid_point(&Point { x, y: row }))
+            .collect()
+    }
+
+    fn column_points(&self, column: usize) -> Option<Vec<Point>> {
+        (0..self.rows)
+            .map(|y| self.grid_point(&Point { x: column, y }))
+            .collect()
+    }
+
+    fn points(&self) -> impl Iterator<Item = Point> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |y| (0..columns).map(move |x| Point { x, y }))
+    }
+
+    fn get_neighbours(&self, point: &Point, neighbourhood: Neighbourhood) -> Vec<Point> {
+        if point.x >= self.columns || point.y >= self.rows {
+            return Vec::new();
+        }
+
+        neighbourhood
+            .directions()
+            .iter()
+            .flat_map(|direction| {
+                let (i, j) = direction.offset();
+                Some(Point {
+                    x: point.x.checked_add_signed(i)?,
+                    y: point.y.checked_add_signed(j)?,
+                })
+            })
+            .filter(|p| p.x < self.columns && p.y < self.rows)
+            .collect()
+    }
+
+    fn is_edge_node(&self, point: &Point) -> bool {
+        (point.x == 0 || point.x == self.columns - 1) || (point.y == 0 || point.y == self.rows - 1)
+    }
+}
+
+impl<T: Display + Clone> Display for GridView<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (point, element) in self.iter() {
+            write!(f, "{}", element)?;
+            if point.x == self.rect.columns - 1 {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_grid() -> Grid<u8> {
+        let mut grid = Grid::<u8>::new();
+        grid.add_row(vec![0, 0, 1, 5]);
+        grid.add_row(vec![1, 3, 1, 7]);
+        grid.add_row(vec![8, 7, 1, 10]);
+        grid.add_row(vec![99, 2, 1, 12]);
+        grid.add_row(vec![9, 20, 61, 2]);
+        grid
+    }
+
+    #[test]
+    fn test_view_accessors() {
+        let grid = get_test_grid();
+        let view = grid.view(Point { x: 1, y: 2 }, 3, 2).unwrap();
+
+        assert_eq!((3, 2), (view.columns(), view.rows()));
+        assert_eq!(Some(&7), view.get_element(&Point { x: 0, y: 0 }));
+        assert_eq!(Some(&12), view.get_element(&Point { x: 2, y: 1 }));
+        assert_eq!(None, view.get_element(&Point { x: 3, y: 0 }));
+        assert_eq!(Some(vec![2, 1, 12]), view.get_row(1));
+        assert_eq!(None, view.get_row(2));
+        assert_eq!(Some(vec![1, 1]), view.get_column(1));
+        assert_eq!(
+            Some(Point { x: 3, y: 3 }),
+            view.to_grid_point(&Point { x: 2, y: 1 })
+        );
+
+        assert_eq!(
+            vec![Point { x: 0, y: 1 }, Point { x: 1, y: 0 }],
+            view.get_adjacent_points(&Point { x: 0, y: 0 })
+        );
+        assert_eq!(
+            5,
+            view.get_neighbours(&Point { x: 1, y: 0 }, Neighbourhood::All)
+                .len()
+        );
+        assert!(view.is_edge_node(&Point { x: 1, y: 1 }));
+        assert_eq!("7110\n2112\n", view.to_string());
+    }
+
+    #[test]
+    fn test_view_must_fit() {
+        let grid = get_test_grid();
+        assert!(grid.view(Point { x: 1, y: 2 }, 4, 2).is_none());
+        assert!(grid.view(Point { x: 0, y: 4 }, 1, 2).is_none());
+        assert!(grid.view(Point { x: 0, y: 0 }, 0, 2).is_none());
+        assert!(grid.view(Point { x: 0, y: 0 }, 4, 5).is_some());
+        let far = Point {
+            x: usize::MAX,
+            y: 0,
+        };
+        assert!(grid.view(far, 1, 1).is_none());
+        assert!(grid.view(Point { x: 0, y: 1 }, 1, usize::MAX).is_none());
+    }
+
+    #[test]
+    fn test_inner_view_matches_inner_grid() {
+        let grid = get_test_grid();
+        assert_eq!(grid.get_inner_grid(), grid.inner_view().unwrap().to_grid());
+    }
+
+    #[test]
+    fn test_view_mut() {
+        let mut grid = get_test_grid();
+        let mut view = grid.view_mut(Point { x: 2, y: 0 }, 2, 2).unwrap();
+
+        *view.get_element_mut(&Point { x: 1, y: 1 }).unwrap() = 50;
+        assert_eq!(Some(vec![5, 50]), view.get_column(1));
+        assert_eq!(None, view.get_element_mut(&Point { x: 2, y: 0 }));
+
+        view.fill(0);
+        assert_eq!(vec![0, 0, 0, 0], grid.get_row(0).unwrap());
+        assert_eq!(vec![1, 3, 0, 0], grid.get_row(1).unwrap());
+    }
+
+    #[test]
+    fn test_windows() {
+        let grid = get_test_grid();
+
+        let windows: Vec<GridView<'_, u8>> = grid.windows(3, 2).collect();
+        assert_eq!(2 * 4, windows.len());
+        assert_eq!(Point { x: 0, y: 0 }, windows[0].origin());
+        assert_eq!(Point { x: 1, y: 0 }, windows[1].origin());
+        assert_eq!(Point { x: 0, y: 1 }, windows[2].origin());
+
+        // find the 2x2 window with the largest sum
+        let best = grid
+            .windows(2, 2)
+            .max_by_key(|w| w.iter().map(|(_, e)| *e as u32).sum::<u32>())
+            .unwrap();
+        assert_eq!(Point { x: 0, y: 3 }, best.origin());
+
+        assert_eq!(0, grid.windows(5, 1).count());
+        assert_eq!(1, grid.windows(4, 5).count());
+    }
+}
@@ -0,0 +1,436 @@
+use std::fmt::Display;
+
+use crate::common::grid::Grid;
+use crate::common::point::{Coordinate, OutOfRange};
+
+pub mod sparse;
+
+/// Grid3 is a 3-dimensional array of layers, each of which is a row-major 2D grid. Layer size is fixed, but it can
+/// have as many layers as you want. Layers are added with the `add_layer` function.
+///
+/// If the layer size is not set with `with_layer_size`, it will be set as the size of the first layer added.
+#[derive(Debug, Clone)]
+pub struct Grid3<T> {
+    pub elements: Vec<T>,
+    pub columns: usize,
+    pub rows: usize,
+    pub layers: usize,
+}
+
+/// A 3-dimensional point. Defaults to `usize` coordinates, as used to index a `Grid3`; signed coordinates
+/// are used for unbounded positions, as in `SparseGrid3`.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Ord, PartialOrd, Hash, Default)]
+pub struct Point3<N = usize> {
+    pub x: N,
+    pub y: N,
+    pub z: N,
+}
+
+/// Which of the surrounding cells count as neighbours of a point.
+#[allow(dead_code)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Neighbourhood3 {
+    /// The six cells sharing a face.
+    Faces,
+    /// All twenty-six surrounding cells.
+    All,
+}
+
+/// The axis a slice is taken across.
+///     X: a grid of y columns by z rows.
+///     Y: a grid of x columns by z rows.
+///     Z: a grid of x columns by y rows, i.e. a layer.
+#[allow(dead_code)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+#[allow(dead_code)]
+impl<T: Clone> Grid3<T> {
+    pub fn new() -> Self {
+        Grid3 {
+            elements: Vec::<T>::new(),
+            columns: 0,
+            rows: 0,
+            layers: 0,
+        }
+    }
+
+    /// Create a grid of the given size with every element set to `value`.
+    pub fn filled(columns: usize, rows: usize, layers: usize, value: T) -> Self {
+        Grid3 {
+            elements: vec![value; columns * rows * layers],
+            columns,
+            rows,
+            layers,
+        }
+    }
+
+    pub fn with_layer_size(mut self, columns: usize, rows: usize) -> Self {
+        if self.columns == 0 && self.rows == 0 {
+            self.columns = columns;
+            self.rows = rows;
+        }
+
+        self
+    }
+
+    pub fn add_layer(&mut self, layer: Grid<T>) {
+        if self.columns == 0 && self.rows == 0 {
+            self.columns = layer.columns;
+            self.rows = layer.rows;
+        } else if layer.columns != self.columns || layer.rows != self.rows {
+            eprintln!("Layer size does not equal layer size of grid.");
+            return;
+        }
+
+        self.elements.extend(layer.elements);
+        self.layers += 1;
+    }
+
+    pub fn get_layer(&self, layer: usize) -> Option<Grid<T>> {
+        self.slice(Axis::Z, layer)
+    }
+
+    /// Get the 2D grid of elements at `index` along `axis`. See `Axis` for the layout of the result.
+    pub fn slice(&self, axis: Axis, index: usize) -> Option<Grid<T>> {
+        let (columns, rows, size) = match axis {
+            Axis::X => (self.rows, self.layers, self.columns),
+            Axis::Y => (self.columns, self.layers, self.rows),
+            Axis::Z => (self.columns, self.rows, self.layers),
+        };
+
+        if index >= size {
+            return None;
+        }
+
+        let mut grid = Grid::new().with_column_size(columns);
+
+        for j in 0..rows {
+            let row = (0..columns)
+                .map(|i| {
+                    let point = match axis {
+                        Axis::X => Point3 {
+                            x: index,
+                            y: i,
+                            z: j,
+                        },
+                        Axis::Y => Point3 {
+                            x: i,
+                            y: index,
+                            z: j,
+                        },
+                        Axis::Z => Point3 {
+                            x: i,
+                            y: j,
+                            z: index,
+                        },
+                    };
+                    self.get_element(&point)
+                        .cloned()
+                        .expect("Point should be in grid")
+                })
+                .collect();
+            grid.add_row(row);
+        }
+
+        Some(grid)
+    }
+
+    /// Convert a element vector index to a xyz coordinate point
+    pub fn index_to_point(&self, index: usize) -> Option<Point3> {
+        if index >= self.elements.len() {
+            return None;
+        }
+
+        let layer_size = self.columns * self.rows;
+
+        Some(Point3 {
+            x: index % self.columns,
+            y: (index % layer_size) / self.columns,
+            z: index / layer_size,
+        })
+    }
+
+    pub fn get_element(&self, point: &Point3) -> Option<&T> {
+        if !self.is_in_bounds(point) {
+            return None;
+        }
+
+        self.elements.get(self.point_to_index(point))
+    }
+
+    pub fn get_element_mut(&mut self, point: &Point3) -> Option<&mut T> {
+        if !self.is_in_bounds(point) {
+            return None;
+        }
+
+        let index = self.point_to_index(point);
+        self.elements.get_mut(index)
+    }
+
+    pub fn get_adjacent_points(&self, point: &Point3) -> Vec<Point3> {
+        self.get_neighbours(point, Neighbourhood3::Faces)
+    }
+
+    /// Get the in-bounds points around `point` for the given neighbourhood.
+    pub fn get_neighbours(&self, point: &Point3, neighbourhood: Neighbourhood3) -> Vec<Point3> {
+        if !self.is_in_bounds(point) {
+            return Vec::new();
+        }
+
+        neighbourhood
+            .offsets()
+            .iter()
+            .flat_map(|(i, j, k)| {
+                Some(Point3 {
+                    x: point.x.checked_add_signed(*i)?,
+                    y: point.y.checked_add_signed(*j)?,
+                    z: point.z.checked_add_signed(*k)?,
+                })
+            })
+            .filter(|p| self.is_in_bounds(p))
+            .collect()
+    }
+
+    pub fn is_edge_node(&self, point: &Point3) -> bool {
+        (point.x == 0 || point.x == self.columns - 1)
+            || (point.y == 0 || point.y == self.rows - 1)
+            || (point.z == 0 || point.z == self.layers - 1)
+    }
+
+    /// Smallest box, as inclusive (min, max) corners, holding every element matching `predicate`.
+    pub fn bounding_box<P>(&self, predicate: P) -> Option<(Point3, Point3)>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.elements
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| predicate(e))
+            .flat_map(|(index, _)| self.index_to_point(index))
+            .fold(None, |bounds, p| match bounds {
+                None => Some((p, p)),
+                Some((min, max)) => Some((min.min_each(&p), max.max_each(&p))),
+            })
+    }
+
+    fn is_in_bounds(&self, point: &Point3) -> bool {
+        point.x < self.columns && point.y < self.rows && point.z < self.layers
+    }
+
+    fn point_to_index(&self, point: &Point3) -> usize {
+        (point.z * self.rows + point.y) * self.columns + point.x
+    }
+}
+
+#[allow(dead_code)]
+impl Neighbourhood3 {
+    /// (x, y, z) offsets from the centre point, in a fixed order.
+    pub fn offsets(&self) -> Vec<(isize, isize, isize)> {
+        let mut offsets = Vec::new();
+
+        for i in -1..=1_isize {
+            for j in -1..=1_isize {
+                for k in -1..=1_isize {
+                    let moved_axes = i.abs() + j.abs() + k.abs();
+                    let include = match self {
+                        Neighbourhood3::Faces => moved_axes == 1,
+                        Neighbourhood3::All => moved_axes != 0,
+                    };
+
+                    if include {
+                        offsets.push((i, j, k));
+                    }
+                }
+            }
+        }
+
+        offsets
+    }
+}
+
+#[allow(dead_code)]
+impl<N: Coordinate> Point3<N> {
+    pub fn new(x: N, y: N, z: N) -> Self {
+        Point3 { x, y, z }
+    }
+
+    /// The smaller of each coordinate of the two points.
+    pub fn min_each(&self, other: &Self) -> Self {
+        Point3 {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+            z: self.z.min(other.z),
+        }
+    }
+
+    /// The larger of each coordinate of the two points.
+    pub fn max_each(&self, other: &Self) -> Self {
+        Point3 {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+            z: self.z.max(other.z),
+        }
+    }
+
+    /// Convert to another coordinate type, e.g. `usize` to `i64` and back. None if any coordinate doesn't
+    /// fit in the new type.
+    pub fn checked_cast<M: TryFrom<N>>(&self) -> Option<Point3<M>> {
+        Some(Point3 {
+            x: M::try_from(self.x).ok()?,
+            y: M::try_from(self.y).ok()?,
+            z: M::try_from(self.z).ok()?,
+        })
+    }
+}
+
+impl<N: Display> Display for Point3<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(x: {}, y: {}, z: {})", self.x, self.y, self.z)
+    }
+}
+
+impl TryFrom<Point3<usize>> for Point3<i64> {
+    type Error = OutOfRange;
+
+    fn try_from(point: Point3<usize>) -> Result<Self, Self::Error> {
+        point.checked_cast().ok_or(OutOfRange)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Grid3<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.elements == other.elements
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: usize, y: usize, z: usize) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    // 3 columns, 2 rows, 2 layers. The value is the xyz of each element as a number.
+    fn get_test_grid() -> Grid3<u16> {
+        let mut grid = Grid3::<u16>::new();
+
+        let mut layer = Grid::new();
+        layer.add_row(vec![0, 100, 200]);
+        layer.add_row(vec![10, 110, 210]);
+        grid.add_layer(layer);
+
+        let mut layer = Grid::new();
+        layer.add_row(vec![1, 101, 201]);
+        layer.add_row(vec![11, 111, 211]);
+        grid.add_layer(layer);
+
+        grid
+    }
+
+    #[test]
+    fn test_grid_creation() {
+        let mut grid = get_test_grid();
+        assert_eq!(3, grid.columns);
+        assert_eq!(2, grid.rows);
+        assert_eq!(2, grid.layers);
+
+        let mut small = Grid::new();
+        small.add_row(vec![0, 0]);
+        grid.add_layer(small);
+        assert_eq!(2, grid.layers);
+
+        assert_eq!(12, Grid3::filled(3, 2, 2, 0).elements.len());
+        assert_eq!(grid, grid.clone());
+        assert_eq!("(x: -1, y: 0, z: 2)", Point3::new(-1, 0, 2).to_string());
+    }
+
+    #[test]
+    fn test_get_element_and_index_to_point() {
+        let grid = get_test_grid();
+        assert_eq!(Some(&210), grid.get_element(&p(2, 1, 0)));
+        assert_eq!(Some(&101), grid.get_element(&p(1, 0, 1)));
+        assert_eq!(None, grid.get_element(&p(1, 0, 2)));
+
+        for (index, value) in grid.elements.iter().enumerate() {
+            let point = grid.index_to_point(index).unwrap();
+            assert_eq!(*value as usize, point.x * 100 + point.y * 10 + point.z);
+        }
+        assert_eq!(None, grid.index_to_point(12));
+    }
+
+    #[test]
+    fn test_get_neighbours() {
+        let grid = Grid3::filled(3, 3, 3, 0);
+
+        assert_eq!(6, grid.get_adjacent_points(&p(1, 1, 1)).len());
+        assert_eq!(
+            26,
+            grid.get_neighbours(&p(1, 1, 1), Neighbourhood3::All).len()
+        );
+        assert_eq!(3, grid.get_adjacent_points(&p(0, 0, 0)).len());
+        assert_eq!(
+            7,
+            grid.get_neighbours(&p(2, 2, 2), Neighbourhood3::All).len()
+        );
+        assert!(grid.get_adjacent_points(&p(3, 0, 0)).is_empty());
+
+        let mut expected = vec![p(1, 0, 0), p(0, 1, 0), p(0, 0, 1)];
+        expected.sort();
+        let mut actual = grid.get_adjacent_points(&p(0, 0, 0));
+        actual.sort();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_slices() {
+        let grid = get_test_grid();
+
+        let mut expected = Grid::new();
+        expected.add_row(vec![1, 101, 201]);
+        expected.add_row(vec![11, 111, 211]);
+        assert_eq!(expected, grid.get_layer(1).unwrap());
+
+        let mut expected = Grid::new();
+        expected.add_row(vec![200, 210]);
+        expected.add_row(vec![201, 211]);
+        let slice = grid.slice(Axis::X, 2).unwrap();
+        assert_eq!(expected, slice);
+        assert_eq!((2, 2), (slice.columns, slice.rows));
+
+        let mut expected = Grid::new();
+        expected.add_row(vec![10, 110, 210]);
+        expected.add_row(vec![11, 111, 211]);
+        assert_eq!(expected, grid.slice(Axis::Y, 1).unwrap());
+
+        assert!(grid.slice(Axis::X, 3).is_none());
+        assert!(grid.slice(Axis::Z, 2).is_none());
+    }
+
+    #[test]
+    fn test_is_edge_node() {
+        let grid = Grid3::filled(3, 3, 3, 0);
+        assert!(grid.is_edge_node(&p(0, 1, 1)));
+        assert!(grid.is_edge_node(&p(1, 1, 2)));
+        assert!(!grid.is_edge_node(&p(1, 1, 1)));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let grid = get_test_grid();
+        assert_eq!(
+            Some((p(1, 0, 1), p(2, 1, 1))),
+            grid.bounding_box(|e| *e >= 100 && *e % 2 == 1)
+        );
+        assert_eq!(Some((p(0, 0, 0), p(2, 1, 1))), grid.bounding_box(|_| true));
+        assert_eq!(None, grid.bounding_box(|e| *e > 1000));
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use super::{Grid3, Neighbourhood3, Point3};
+
+/// SparseGrid3 is an unbounded 3-dimensional grid with signed coordinates. Only cells that have been set
+/// are stored, so it can grow in any direction. The bounding box of all set cells is tracked as cells are
+/// added and removed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseGrid3<T> {
+    cells: HashMap<SparsePoint3, T>,
+    bounds: Option<Bounds3>,
+}
+
+/// Signed point used to index a `SparseGrid3`.
+pub type SparsePoint3 = Point3<i64>;
+
+/// Inclusive box covering every set cell of a `SparseGrid3`.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Bounds3 {
+    pub min: SparsePoint3,
+    pub max: SparsePoint3,
+}
+
+#[allow(dead_code)]
+impl<T> SparseGrid3<T> {
+    pub fn new() -> Self {
+        SparseGrid3 {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn bounds(&self) -> Option<Bounds3> {
+        self.bounds
+    }
+
+    pub fn contains(&self, point: &SparsePoint3) -> bool {
+        self.cells.contains_key(point)
+    }
+
+    pub fn get(&self, point: &SparsePoint3) -> Option<&T> {
+        self.cells.get(point)
+    }
+
+    pub fn get_mut(&mut self, point: &SparsePoint3) -> Option<&mut T> {
+        self.cells.get_mut(point)
+    }
+
+    /// Set the value at `point`, returning the previous value if there was one.
+    pub fn set(&mut self, point: SparsePoint3, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some(bounds) => bounds.extend(&point),
+            None => Bounds3 {
+                min: point,
+                max: point,
+            },
+        });
+
+        self.cells.insert(point, value)
+    }
+
+    /// Remove the value at `point`. The bounding box shrinks if the point was on its surface.
+    pub fn remove(&mut self, point: &SparsePoint3) -> Option<T> {
+        let removed = self.cells.remove(point);
+
+        if removed.is_some() && self.bounds.is_some_and(|b| b.is_on_surface(point)) {
+            self.recalculate_bounds();
+        }
+
+        removed
+    }
+
+    /// Remove every cell.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds = None;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&SparsePoint3, &T)> {
+        self.cells.iter()
+    }
+
+    /// Get the points around `point` for the given neighbourhood, whether or not they are set.
+    pub fn get_neighbours(
+        &self,
+        point: &SparsePoint3,
+        neighbourhood: Neighbourhood3,
+    ) -> Vec<SparsePoint3> {
+        neighbourhood
+            .offsets()
+            .iter()
+            .map(|(i, j, k)| SparsePoint3 {
+                x: point.x + *i as i64,
+                y: point.y + *j as i64,
+                z: point.z + *k as i64,
+            })
+            .collect()
+    }
+
+    /// Get the set points, and their values, around `point` for the given neighbourhood.
+    pub fn get_set_neighbours(
+        &self,
+        point: &SparsePoint3,
+        neighbourhood: Neighbourhood3,
+    ) -> Vec<(SparsePoint3, &T)> {
+        self.get_neighbours(point, neighbourhood)
+            .into_iter()
+            .flat_map(|p| self.get(&p).map(|v| (p, v)))
+            .collect()
+    }
+
+    fn recalculate_bounds(&mut self) {
+        self.bounds = self.cells.keys().fold(None, |bounds, p| {
+            Some(match bounds {
+                Some(b) => b.extend(p),
+                None => Bounds3 { min: *p, max: *p },
+            })
+        });
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Clone> SparseGrid3<T> {
+    /// Convert to a dense `Grid3` covering the bounding box. The lowest corner of the bounding box becomes
+    /// (0, 0, 0), and cells that are not set are filled with `default`. None if the bounding box has more
+    /// cells than a `Grid3` can index.
+    pub fn to_grid3(&self, default: T) -> Option<Grid3<T>> {
+        let Some(bounds) = self.bounds else {
+            return Some(Grid3::new());
+        };
+
+        let (columns, rows, layers) = bounds.size()?;
+        columns.checked_mul(rows)?.checked_mul(layers)?;
+        let mut grid = Grid3::filled(columns, rows, layers, default);
+
+        // every point is in the bounds, whose size has been checked, so its offset fits
+        let offset = |min: i64, value: i64| (value - min) as usize;
+        for (point, value) in self.cells.iter() {
+            let dense = Point3 {
+                x: offset(bounds.min.x, point.x),
+                y: offset(bounds.min.y, point.y),
+                z: offset(bounds.min.z, point.z),
+            };
+            *grid
+                .get_element_mut(&dense)
+                .expect("Point should be in grid") = value.clone();
+        }
+
+        Some(grid)
+    }
+}
+
+impl<T> Default for SparseGrid3<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> From<&Grid3<T>> for SparseGrid3<T> {
+    fn from(grid: &Grid3<T>) -> Self {
+        let mut sparse = SparseGrid3::new();
+
+        for (index, value) in grid.elements.iter().enumerate() {
+            let point = grid.index_to_point(index).expect("Index should be in grid");
+            let point = point.try_into().expect("Grid index should fit in i64");
+            sparse.set(point, value.clone());
+        }
+
+        sparse
+    }
+}
+
+#[allow(dead_code)]
+impl Bounds3 {
+    /// Number of (columns, rows, layers) covered. None if a side is too long for a `usize`.
+    pub fn size(&self) -> Option<(usize, usize, usize)> {
+        Some((
+            span(self.min.x, self.max.x)?,
+            span(self.min.y, self.max.y)?,
+            span(self.min.z, self.max.z)?,
+        ))
+    }
+
+    pub fn contains(&self, point: &SparsePoint3) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    fn extend(&self, point: &SparsePoint3) -> Self {
+        Bounds3 {
+            min: self.min.min_each(point),
+            max: self.max.max_each(point),
+        }
+    }
+
+    fn is_on_surface(&self, point: &SparsePoint3) -> bool {
+        point.x == self.min.x
+            || point.x == self.max.x
+            || point.y == self.min.y
+            || point.y == self.max.y
+            || point.z == self.min.z
+            || point.z == self.max.z
+    }
+}
+
+// number of values from `min` to `max` inclusive, if it fits in a usize
+fn span(min: i64, max: i64) -> Option<usize> {
+    usize::try_from(max.checked_sub(min)?).ok()?.checked_add(1)
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::point::OutOfRange;
+
+    fn p(x: i64, y: i64, z: i64) -> SparsePoint3 {
+        SparsePoint3 { x, y, z }
+    }
+
+    #[test]
+    fn test_set_get_remove_and_bounds() {
+        let mut grid = SparseGrid3::<bool>::new();
+        assert_eq!(None, grid.bounds());
+
+        grid.set(p(-1, 0, 5), true);
+        grid.set(p(2, -3, 0), true);
+        grid.set(p(0, 0, 1), false);
+
+        assert_eq!(3, grid.len());
+        assert_eq!(Some(&false), grid.get(&p(0, 0, 1)));
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(p(-1, -3, 0), bounds.min);
+        assert_eq!(p(2, 0, 5), bounds.max);
+        assert_eq!(Some((4, 4, 6)), bounds.size());
+
+        assert_eq!(Some(true), grid.remove(&p(-1, 0, 5)));
+        assert_eq!(p(0, -3, 0), grid.bounds().unwrap().min);
+        assert_eq!(p(2, 0, 1), grid.bounds().unwrap().max);
+
+        let copy = grid.clone();
+        grid.clear();
+        assert!(grid.is_empty());
+        assert_eq!(None, grid.bounds());
+        assert_eq!(2, copy.len());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let mut grid = SparseGrid3::<u8>::new();
+        grid.set(p(0, 0, -1), 1);
+        grid.set(p(1, 1, 1), 2);
+
+        assert_eq!(
+            6,
+            grid.get_neighbours(&p(0, 0, 0), Neighbourhood3::Faces)
+                .len()
+        );
+        assert_eq!(
+            26,
+            grid.get_neighbours(&p(0, 0, 0), Neighbourhood3::All).len()
+        );
+        assert_eq!(
+            vec![(p(0, 0, -1), &1)],
+            grid.get_set_neighbours(&p(0, 0, 0), Neighbourhood3::Faces)
+        );
+        assert_eq!(
+            2,
+            grid.get_set_neighbours(&p(0, 0, 0), Neighbourhood3::All)
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_grid3_conversion() {
+        let mut sparse = SparseGrid3::<u8>::new();
+        sparse.set(p(-1, 0, 0), 1);
+        sparse.set(p(0, 1, 2), 2);
+
+        let dense = sparse.to_grid3(0).unwrap();
+        assert_eq!((2, 2, 3), (dense.columns, dense.rows, dense.layers));
+        assert_eq!(Some(&1), dense.get_element(&Point3 { x: 0, y: 0, z: 0 }));
+        assert_eq!(Some(&2), dense.get_element(&Point3 { x: 1, y: 1, z: 2 }));
+        assert_eq!(10, dense.elements.iter().filter(|e| **e == 0).count());
+
+        let back = SparseGrid3::from(&dense);
+        assert_eq!(12, back.len());
+        assert_eq!(Some(&2), back.get(&p(1, 1, 2)));
+        assert_eq!(
+            Err(OutOfRange),
+            SparsePoint3::try_from(Point3::new(0, usize::MAX, 0))
+        );
+    }
+
+    #[test]
+    fn test_bounds_too_large_for_a_grid3() {
+        let mut sparse = SparseGrid3::<u8>::new();
+        sparse.set(p(i64::MIN, 0, 0), 1);
+        sparse.set(p(i64::MAX, 0, 0), 2);
+        assert_eq!(None, sparse.bounds().unwrap().size());
+        assert_eq!(None, sparse.to_grid3(0));
+
+        // each side fits, but the number of cells doesn't
+        let mut sparse = SparseGrid3::<u8>::new();
+        sparse.set(p(0, 0, 0), 1);
+        sparse.set(p(1 << 30, 1 << 30, 1 << 30), 2);
+        assert!(sparse.bounds().unwrap().size().is_some());
+        assert_eq!(None, sparse.to_grid3(0));
+    }
+}
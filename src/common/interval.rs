@@ -0,0 +1,348 @@
+use std::fmt::Display;
+
+/// A closed interval, holding every value from `start` to `end` inclusive. An interval is never empty:
+/// `start` is always at most `end`.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Ord, PartialOrd, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+/// A set of values, kept as sorted intervals that neither overlap nor touch. Adding an interval merges
+/// it with any it overlaps or is next to, and removing one splits any interval it falls inside.
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct RangeSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+/// Values with a next and previous value, so that intervals of them can be counted and can be next to
+/// each other.
+pub trait Discrete: Ord + Copy {
+    fn successor(self) -> Option<Self>;
+
+    fn predecessor(self) -> Option<Self>;
+
+    /// Number of values from `start` to `end` inclusive, saturating at `u64::MAX`.
+    fn count(start: Self, end: Self) -> u64;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {$(
+        impl Discrete for $t {
+            fn successor(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn predecessor(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            fn count(start: Self, end: Self) -> u64 {
+                u64::try_from(end as i128 - start as i128 + 1).unwrap_or(u64::MAX)
+            }
+        }
+    )*};
+}
+
+impl_discrete!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+#[allow(dead_code)]
+impl<T: Ord + Copy> Interval<T> {
+    /// The interval from `start` to `end`, or None if `start` is after `end`.
+    pub fn new(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    /// The interval holding just `value`.
+    pub fn single(value: T) -> Self {
+        Interval {
+            start: value,
+            end: value,
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// True if every value of `other` is in this interval.
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Values in both intervals, if there are any.
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// Values in either interval, if they overlap so that the result is one interval.
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        self.overlaps(other).then(|| self.span(other))
+    }
+
+    /// The smallest interval holding both intervals.
+    pub fn span(&self, other: &Interval<T>) -> Interval<T> {
+        Interval {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Discrete> Interval<T> {
+    /// Number of values in the interval.
+    pub fn len(&self) -> u64 {
+        T::count(self.start, self.end)
+    }
+
+    /// True if the intervals overlap, or one ends just before the other starts.
+    pub fn touches(&self, other: &Interval<T>) -> bool {
+        self.overlaps(other)
+            || self.end.successor() == Some(other.start)
+            || other.end.successor() == Some(self.start)
+    }
+}
+
+#[allow(dead_code)]
+impl<T: Discrete> RangeSet<T> {
+    pub fn new() -> Self {
+        RangeSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn from_intervals(intervals: impl IntoIterator<Item = Interval<T>>) -> Self {
+        let mut set = RangeSet::new();
+        intervals.into_iter().for_each(|i| set.insert(i));
+        set
+    }
+
+    /// The intervals of the set, in order.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|i| i.end < value);
+        self.intervals.get(index).is_some_and(|i| i.contains(value))
+    }
+
+    /// Add every value of `interval`.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // intervals from `first` to `last` (exclusive) overlap or touch the new one
+        let first = self
+            .intervals
+            .partition_point(|i| i.end < interval.start && !i.touches(&interval));
+        let last = first
+            + self.intervals[first..]
+                .iter()
+                .take_while(|i| i.touches(&interval))
+                .count();
+
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |acc, i| acc.span(i));
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// Take out every value of `interval`.
+    pub fn remove(&mut self, interval: Interval<T>) {
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = first
+            + self.intervals[first..]
+                .iter()
+                .take_while(|i| i.overlaps(&interval))
+                .count();
+
+        let mut kept = Vec::new();
+        for i in self.intervals[first..last].iter() {
+            if let Some(before) = interval.start.predecessor() {
+                kept.extend(Interval::new(i.start, before.min(i.end)));
+            }
+            if let Some(after) = interval.end.successor() {
+                kept.extend(Interval::new(after.max(i.start), i.end));
+            }
+        }
+        self.intervals.splice(first..last, kept);
+    }
+
+    /// Values in either set.
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut set = self.clone();
+        other.intervals.iter().for_each(|i| set.insert(*i));
+        set
+    }
+
+    /// Values in this set but not in `other`.
+    pub fn subtract(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut set = self.clone();
+        other.intervals.iter().for_each(|i| set.remove(*i));
+        set
+    }
+
+    /// Number of values in the set.
+    pub fn coverage(&self) -> u64 {
+        self.intervals
+            .iter()
+            .fold(0, |total, i| total.saturating_add(i.len()))
+    }
+
+    /// The smallest interval holding every value of the set.
+    pub fn span(&self) -> Option<Interval<T>> {
+        let first = self.intervals.first()?;
+        let last = self.intervals.last()?;
+        Some(first.span(last))
+    }
+
+    /// Intervals between the first and last values of the set that aren't in it.
+    pub fn gaps(&self) -> Vec<Interval<T>> {
+        self.intervals
+            .windows(2)
+            .filter_map(|pair| {
+                Interval::new(pair[0].end.successor()?, pair[1].start.predecessor()?)
+            })
+            .collect()
+    }
+
+    /// Intervals of `bounds` that aren't in the set.
+    pub fn gaps_within(&self, bounds: Interval<T>) -> Vec<Interval<T>> {
+        RangeSet::from_intervals([bounds]).subtract(self).intervals
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: i32, end: i32) -> Interval<i32> {
+        Interval::new(start, end).unwrap()
+    }
+
+    fn set(intervals: &[(i32, i32)]) -> RangeSet<i32> {
+        RangeSet::from_intervals(intervals.iter().map(|(s, e)| interval(*s, *e)))
+    }
+
+    fn pairs(set: &RangeSet<i32>) -> Vec<(i32, i32)> {
+        set.intervals()
+            .iter()
+            .map(|i| (i.start(), i.end()))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = interval(2, 6);
+        let b = interval(4, 8);
+        let c = interval(7, 9);
+
+        assert_eq!(None, Interval::new(3, 2));
+        assert!(a.contains(2) && a.contains(6) && !a.contains(7));
+        assert!(a.contains_interval(&interval(3, 6)));
+        assert!(!a.contains_interval(&b));
+        assert!(a.overlaps(&b) && b.overlaps(&a));
+        assert!(!a.overlaps(&c));
+
+        assert_eq!(Some(interval(4, 6)), a.intersection(&b));
+        assert_eq!(None, a.intersection(&c));
+        assert_eq!(Some(interval(2, 8)), a.union(&b));
+        assert_eq!(None, a.union(&c));
+        assert_eq!(interval(2, 9), a.span(&c));
+
+        assert_eq!(5, a.len());
+        assert!(a.touches(&c) && c.touches(&a));
+        assert!(!a.touches(&interval(8, 9)));
+        assert_eq!("2-6", a.to_string());
+    }
+
+    #[test]
+    fn test_count_at_limits() {
+        assert_eq!(256, Interval::new(0u8, 255).unwrap().len());
+        assert_eq!(u64::MAX, Interval::new(0u64, u64::MAX).unwrap().len());
+        assert_eq!(u64::MAX, Interval::new(i64::MIN, i64::MAX).unwrap().len());
+        assert!(!Interval::single(u8::MAX).touches(&Interval::single(0)));
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let mut s = set(&[(10, 12), (1, 3), (20, 25)]);
+        assert_eq!(vec![(1, 3), (10, 12), (20, 25)], pairs(&s));
+
+        // touching intervals merge, as do all intervals an insert overlaps
+        s.insert(interval(4, 4));
+        assert_eq!(vec![(1, 4), (10, 12), (20, 25)], pairs(&s));
+        s.insert(interval(11, 21));
+        assert_eq!(vec![(1, 4), (10, 25)], pairs(&s));
+        s.insert(interval(-5, 30));
+        assert_eq!(vec![(-5, 30)], pairs(&s));
+
+        assert!(s.contains(-5) && s.contains(30) && !s.contains(31));
+    }
+
+    #[test]
+    fn test_remove_and_subtract() {
+        let mut s = set(&[(1, 10), (20, 30)]);
+        s.remove(interval(5, 6));
+        assert_eq!(vec![(1, 4), (7, 10), (20, 30)], pairs(&s));
+        s.remove(interval(8, 25));
+        assert_eq!(vec![(1, 4), (7, 7), (26, 30)], pairs(&s));
+        s.remove(interval(0, 100));
+        assert!(s.is_empty());
+
+        let s = set(&[(1, 10)]).subtract(&set(&[(0, 2), (9, 9)]));
+        assert_eq!(vec![(3, 8), (10, 10)], pairs(&s));
+
+        let mut s = RangeSet::from_intervals([Interval::new(0u8, 255).unwrap()]);
+        s.remove(Interval::new(0, 0).unwrap());
+        s.remove(Interval::new(255, 255).unwrap());
+        assert_eq!(254, s.coverage());
+    }
+
+    #[test]
+    fn test_coverage_and_gaps() {
+        let s = set(&[(1, 3), (5, 5), (8, 10)]);
+
+        assert_eq!(7, s.coverage());
+        assert_eq!(Some(interval(1, 10)), s.span());
+        assert_eq!(vec![interval(4, 4), interval(6, 7)], s.gaps());
+        assert_eq!(
+            vec![
+                interval(0, 0),
+                interval(4, 4),
+                interval(6, 7),
+                interval(11, 12)
+            ],
+            s.gaps_within(interval(0, 12))
+        );
+
+        let u = s.union(&set(&[(4, 4), (6, 7)]));
+        assert_eq!(vec![(1, 10)], pairs(&u));
+        assert!(u.gaps().is_empty());
+        assert_eq!(None, RangeSet::<i32>::new().span());
+    }
+}
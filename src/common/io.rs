@@ -1,5 +1,8 @@
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read};
+use std::iter;
+
+use crate::common::str::{self, Line, Section};
 
 pub fn read_file_as_vector(filename: &str) -> Result<Vec<String>, std::io::Error> {
     let file = File::open(filename)?;
@@ -15,6 +18,133 @@ pub fn read_file_as_vector(filename: &str) -> Result<Vec<String>, std::io::Error
     Ok(lines)
 }
 
+/// The whole of an input, read once and kept as a single string. Lines, sections and characters are
+/// borrowed from it rather than copied. The name, usually the file name, is kept for error messages.
+#[derive(Debug)]
+pub struct Input {
+    name: String,
+    text: String,
+}
+
+/// Reads input one line at a time, reusing a single buffer, for inputs too large to hold in memory.
+pub struct LineReader<R> {
+    name: String,
+    reader: R,
+    buffer: String,
+    number: usize,
+}
+
+#[allow(dead_code)]
+impl Input {
+    pub fn new(name: &str, text: impl Into<String>) -> Self {
+        Input {
+            name: name.to_string(),
+            text: text.into(),
+        }
+    }
+
+    pub fn read(filename: &str) -> Result<Self, std::io::Error> {
+        let file = File::open(filename).map_err(|e| with_name(filename, e))?;
+        Self::from_reader(filename, file)
+    }
+
+    pub fn from_reader(name: &str, mut reader: impl Read) -> Result<Self, std::io::Error> {
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .map_err(|e| with_name(name, e))?;
+
+        Ok(Self::new(name, text))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Numbered lines, without their line endings.
+    pub fn lines(&self) -> impl Iterator<Item = Line<'_>> {
+        self.text.lines().enumerate().map(|(i, text)| Line {
+            number: i + 1,
+            text,
+        })
+    }
+
+    /// Runs of lines separated by blank lines.
+    pub fn sections(&self) -> Vec<Section<'_>> {
+        str::sections(self.lines())
+    }
+
+    /// Every character of the input other than line endings. As with `lines`, a line ending is "\n" or
+    /// "\r\n"; any other '\r' is kept.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        let mut chars = self.text.chars().peekable();
+        iter::from_fn(move || loop {
+            match chars.next()? {
+                '\n' => continue,
+                '\r' if chars.peek() == Some(&'\n') => continue,
+                c => return Some(c),
+            }
+        })
+    }
+}
+
+#[allow(dead_code)]
+impl LineReader<BufReader<File>> {
+    pub fn open(filename: &str) -> Result<Self, std::io::Error> {
+        let file = File::open(filename).map_err(|e| with_name(filename, e))?;
+        Ok(Self::new(filename, BufReader::new(file)))
+    }
+}
+
+#[allow(dead_code)]
+impl<R: BufRead> LineReader<R> {
+    pub fn new(name: &str, reader: R) -> Self {
+        LineReader {
+            name: name.to_string(),
+            reader,
+            buffer: String::new(),
+            number: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Read the next line, without its line ending. The line borrows the reader's buffer, so it must be
+    /// dropped before the following line is read. None at the end of the input.
+    pub fn next_line(&mut self) -> Option<Result<Line<'_>, std::io::Error>> {
+        self.buffer.clear();
+
+        match self.reader.read_line(&mut self.buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.number += 1;
+                let text = match self.buffer.strip_suffix('\n') {
+                    Some(text) => text.strip_suffix('\r').unwrap_or(text),
+                    None => &self.buffer,
+                };
+                Some(Ok(Line {
+                    number: self.number,
+                    text,
+                }))
+            }
+            Err(e) => Some(Err(with_name(
+                &format!("{} line {}", self.name, self.number + 1),
+                e,
+            ))),
+        }
+    }
+}
+
+fn with_name(name: &str, error: std::io::Error) -> std::io::Error {
+    std::io::Error::new(error.kind(), format!("{}: {}", name, error))
+}
+
 //
 //
 //
@@ -30,4 +160,63 @@ mod tests {
         let expected_values: Vec<String> = vec!["A Y".to_string(), "B X".into(), "C Z".into()];
         assert_eq!(expected_values, lines);
     }
+
+    #[test]
+    fn test_input() {
+        let input = Input::read("resources/test/01_calories.txt").unwrap();
+
+        assert_eq!("resources/test/01_calories.txt", input.name());
+        assert_eq!(14, input.lines().count());
+        assert_eq!(
+            Some(Line {
+                number: 5,
+                text: "4000"
+            }),
+            input.lines().nth(4)
+        );
+        assert_eq!(5, input.sections().len());
+        assert_eq!(Some(5), input.sections()[1].first_line());
+
+        let error = Input::read("resources/test/missing.txt").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("resources/test/missing.txt: "));
+    }
+
+    #[test]
+    fn test_input_chars() {
+        let input = Input::new("chars", "ab\r\ncd\n");
+        assert_eq!("abcd", input.chars().collect::<String>());
+        assert_eq!(
+            vec!["ab", "cd"],
+            input.lines().map(|l| l.text).collect::<Vec<_>>()
+        );
+
+        // only a '\r' that ends a line is dropped
+        let input = Input::new("carriage returns", "a\rb\r\r\nc\r");
+        assert_eq!("a\rb\rc\r", input.chars().collect::<String>());
+    }
+
+    #[test]
+    fn test_line_reader() {
+        let mut reader = LineReader::new("stream", "first\r\r\n\nthird\r".as_bytes());
+
+        let mut lines = Vec::new();
+        while let Some(line) = reader.next_line() {
+            let line = line.unwrap();
+            lines.push((line.number, line.text.to_string()));
+        }
+
+        assert_eq!(
+            vec![
+                (1, "first\r".to_string()),
+                (2, "".into()),
+                (3, "third\r".into())
+            ],
+            lines
+        );
+        assert!(reader.next_line().is_none());
+        assert!(reader.next_line().is_none());
+        assert_eq!(3, reader.number);
+    }
 }
@@ -0,0 +1,308 @@
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// A 2-dimensional point or vector. Defaults to `usize` coordinates, as used to index a `Grid`; signed
+/// coordinates are used for unbounded positions and for moves between points.
+///
+/// As in `Grid`, `y` increases downwards, so "up" is towards negative `y`.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Ord, PartialOrd, Hash, Default)]
+pub struct Point<N = usize> {
+    pub x: N,
+    pub y: N,
+}
+
+/// A signed move between two points.
+pub type Vec2 = Point<i64>;
+
+/// A point whose coordinates don't fit in the coordinate type it was converted to.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct OutOfRange;
+
+/// Numeric types that can be used as point coordinates.
+pub trait Coordinate:
+    Copy + Ord + Default + Add<Output = Self> + Sub<Output = Self> + AddAssign + SubAssign
+{
+    /// The unsigned type of the same size, which holds the distance between any two values.
+    type Distance: Copy + Ord + Add<Output = Self::Distance>;
+
+    /// Absolute difference between two values.
+    fn distance(self, other: Self) -> Self::Distance;
+}
+
+/// Coordinate types that can be negative.
+pub trait Signed: Coordinate + Neg<Output = Self> {
+    const ONE: Self;
+
+    fn signum(self) -> Self;
+}
+
+macro_rules! impl_unsigned_coordinate {
+    ($($t:ty),*) => {$(
+        impl Coordinate for $t {
+            type Distance = $t;
+
+            fn distance(self, other: Self) -> Self {
+                self.abs_diff(other)
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed_coordinate {
+    ($($t:ty => $u:ty),*) => {$(
+        impl Coordinate for $t {
+            type Distance = $u;
+
+            fn distance(self, other: Self) -> $u {
+                self.abs_diff(other)
+            }
+        }
+
+        impl Signed for $t {
+            const ONE: Self = 1;
+
+            fn signum(self) -> Self {
+                <$t>::signum(self)
+            }
+        }
+    )*};
+}
+
+impl_unsigned_coordinate!(u8, u16, u32, u64, usize);
+impl_signed_coordinate!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+
+#[allow(dead_code)]
+impl<N: Coordinate> Point<N> {
+    pub fn new(x: N, y: N) -> Self {
+        Point { x, y }
+    }
+
+    /// Number of orthogonal steps between the points.
+    pub fn manhattan(&self, other: &Self) -> N::Distance {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    /// Number of steps between the points when diagonal steps are allowed.
+    pub fn chebyshev(&self, other: &Self) -> N::Distance {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+
+    /// The smaller of each coordinate of the two points.
+    pub fn min_each(&self, other: &Self) -> Self {
+        Point {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+        }
+    }
+
+    /// The larger of each coordinate of the two points.
+    pub fn max_each(&self, other: &Self) -> Self {
+        Point {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+        }
+    }
+
+    /// Convert to another coordinate type, e.g. `usize` to `i64` and back. None if either coordinate
+    /// doesn't fit in the new type.
+    pub fn checked_cast<M: TryFrom<N>>(&self) -> Option<Point<M>> {
+        Some(Point {
+            x: M::try_from(self.x).ok()?,
+            y: M::try_from(self.y).ok()?,
+        })
+    }
+}
+
+#[allow(dead_code)]
+impl<N: Signed> Point<N> {
+    /// Each coordinate reduced to -1, 0 or 1. For a difference between two points, this is a single step
+    /// (possibly diagonal) from the second towards the first.
+    pub fn signum(&self) -> Self {
+        Point {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+
+    /// Rotate 90 degrees clockwise about the origin. Up becomes right.
+    pub fn rotate_right(&self) -> Self {
+        Point {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    /// Rotate 90 degrees anticlockwise about the origin. Up becomes left.
+    pub fn rotate_left(&self) -> Self {
+        Point {
+            x: self.y,
+            y: -self.x,
+        }
+    }
+
+    /// The unit move for a direction letter: `U`/`N`, `D`/`S`, `L`/`W` or `R`/`E`.
+    pub fn from_direction_char(c: char) -> Option<Self> {
+        let zero = N::default();
+        let one = N::ONE;
+
+        match c {
+            'U' | 'N' => Some(Point { x: zero, y: -one }),
+            'D' | 'S' => Some(Point { x: zero, y: one }),
+            'L' | 'W' => Some(Point { x: -one, y: zero }),
+            'R' | 'E' => Some(Point { x: one, y: zero }),
+            _ => None,
+        }
+    }
+}
+
+impl<N: Coordinate> Add for Point<N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl<N: Coordinate> Sub for Point<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl<N: Coordinate> AddAssign for Point<N> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<N: Coordinate> SubAssign for Point<N> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<N: Signed> Neg for Point<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl<N: Display> Display for Point<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(x: {}, y: {})", self.x, self.y)
+    }
+}
+
+impl TryFrom<Point<usize>> for Point<i64> {
+    type Error = OutOfRange;
+
+    fn try_from(point: Point<usize>) -> Result<Self, Self::Error> {
+        point.checked_cast().ok_or(OutOfRange)
+    }
+}
+
+impl Display for OutOfRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "coordinate out of range for the point type")
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+//
+//
+//
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Vec2::new(3, -2);
+        let b = Vec2::new(-1, 5);
+
+        assert_eq!(Vec2::new(2, 3), a + b);
+        assert_eq!(Vec2::new(4, -7), a - b);
+        assert_eq!(Vec2::new(-3, 2), -a);
+
+        let mut c = a;
+        c += b;
+        c -= Vec2::new(1, 1);
+        assert_eq!(Vec2::new(1, 2), c);
+
+        assert_eq!(Point { x: 5_usize, y: 3 }, Point::new(2, 1) + Point::new(3, 2));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Vec2::new(3, -2);
+        let b = Vec2::new(-1, 5);
+        assert_eq!(11, a.manhattan(&b));
+        assert_eq!(7, a.chebyshev(&b));
+
+        let a: Point = Point::new(1, 8);
+        let b: Point = Point::new(4, 2);
+        assert_eq!(9, a.manhattan(&b));
+        assert_eq!(9, b.manhattan(&a));
+        assert_eq!(6, a.chebyshev(&b));
+
+        let far = Vec2::new(i64::MIN, i64::MAX);
+        assert_eq!(u64::MAX, far.chebyshev(&Vec2::new(i64::MAX, 0)));
+        assert_eq!(u64::MAX, Vec2::new(0, 0).manhattan(&Vec2::new(i64::MIN, i64::MAX)));
+    }
+
+    #[test]
+    fn test_signum() {
+        assert_eq!(Vec2::new(1, -1), Vec2::new(7, -3).signum());
+        assert_eq!(Vec2::new(0, 1), Vec2::new(0, 2).signum());
+    }
+
+    #[test]
+    fn test_rotation() {
+        let up = Point::<i32>::from_direction_char('U').unwrap();
+        let right = Point::<i32>::from_direction_char('R').unwrap();
+
+        assert_eq!(right, up.rotate_right());
+        assert_eq!(-right, up.rotate_left());
+        assert_eq!(-up, up.rotate_right().rotate_right());
+        assert_eq!(up, up.rotate_left().rotate_right());
+    }
+
+    #[test]
+    fn test_from_direction_char() {
+        assert_eq!(Some(Vec2::new(0, -1)), Vec2::from_direction_char('U'));
+        assert_eq!(Vec2::from_direction_char('N'), Vec2::from_direction_char('U'));
+        assert_eq!(Vec2::from_direction_char('S'), Vec2::from_direction_char('D'));
+        assert_eq!(Vec2::from_direction_char('W'), Vec2::from_direction_char('L'));
+        assert_eq!(Some(Vec2::new(1, 0)), Vec2::from_direction_char('E'));
+        assert_eq!(None, Vec2::from_direction_char('X'));
+    }
+
+    #[test]
+    fn test_checked_cast() {
+        let p: Point = Point::new(2, 7);
+        assert_eq!(Some(Vec2::new(2, 7)), p.checked_cast::<i64>());
+        assert_eq!(Ok(Vec2::new(2, 7)), Vec2::try_from(p));
+        assert_eq!(Err(OutOfRange), Vec2::try_from(Point::new(usize::MAX, 0)));
+
+        assert_eq!(Some(p), Vec2::new(2, 7).checked_cast::<usize>());
+        assert_eq!(None, Vec2::new(-1, 7).checked_cast::<usize>());
+        assert_eq!(None, Point::new(300_u16, 1).checked_cast::<u8>());
+    }
+}
@@ -1,10 +1,322 @@
-pub fn chunk_string(string: &str, chunk_size: usize) -> Vec<String> {
-    let chars: Vec<char> = string.chars().collect();
+use std::fmt::Display;
+use std::iter;
+use std::str::FromStr;
 
-    return chars
-        .chunks(chunk_size)
-        .map(|chunk| chunk.iter().collect::<String>())
-        .collect::<Vec<_>>();
+/// Split `string` into borrowed pieces of `width` characters. The last piece may be shorter.
+///
+/// Panics if `width` is 0.
+pub fn columns(string: &str, width: usize) -> Vec<&str> {
+    assert!(width > 0, "column width must be at least 1");
+
+    let mut starts: Vec<usize> = string
+        .char_indices()
+        .map(|(i, _)| i)
+        .step_by(width)
+        .collect();
+    starts.push(string.len());
+
+    starts.windows(2).map(|w| &string[w[0]..w[1]]).collect()
+}
+
+/// A line of input along with its line number, counting from 1, so that errors can say where they happened.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+/// A run of lines between blank lines.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Section<'a> {
+    pub lines: Vec<Line<'a>>,
+}
+
+/// Failure to parse a line of input. `file` is set once the error has been traced back to a file.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+/// Number each line, starting at 1.
+pub fn numbered_lines(lines: &[String]) -> impl Iterator<Item = Line<'_>> {
+    lines.iter().enumerate().map(|(i, text)| Line {
+        number: i + 1,
+        text,
+    })
+}
+
+/// Split lines into sections separated by blank lines. Several blank lines in a row, or blank lines at the
+/// start or end, don't make empty sections.
+pub fn sections<'a>(lines: impl IntoIterator<Item = Line<'a>>) -> Vec<Section<'a>> {
+    let mut sections = Vec::new();
+    let mut current = Vec::new();
+
+    for line in lines {
+        if line.text.trim().is_empty() {
+            if !current.is_empty() {
+                sections.push(Section {
+                    lines: std::mem::take(&mut current),
+                });
+            }
+        } else {
+            current.push(line);
+        }
+    }
+
+    if !current.is_empty() {
+        sections.push(Section { lines: current });
+    }
+
+    sections
+}
+
+#[allow(dead_code)]
+impl<'a> Section<'a> {
+    /// Line number of the first line in the section, if it has any lines.
+    pub fn first_line(&self) -> Option<usize> {
+        self.lines.first().map(|l| l.number)
+    }
+
+    pub fn numbered(&self) -> impl Iterator<Item = Line<'a>> + '_ {
+        self.lines.iter().copied()
+    }
+
+    /// The line at `index` within the section. An error if the section is too short, reported against its
+    /// last line, or against line 0 if the section is empty.
+    pub fn line(&self, index: usize) -> Result<Line<'a>, ParseError> {
+        let Some(last) = self.lines.last() else {
+            let start = Line {
+                number: 0,
+                text: "",
+            };
+            return Err(start.error("section is empty"));
+        };
+
+        self.lines
+            .get(index)
+            .copied()
+            .ok_or_else(|| last.error(format!("section ends before line {} of it", index + 1)))
+    }
+}
+
+#[allow(dead_code)]
+impl ParseError {
+    /// Record the file the error came from.
+    pub fn in_file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_string());
+        self
+    }
+}
+
+#[allow(dead_code)]
+impl<'a> Line<'a> {
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            file: None,
+            line: self.number,
+            text: self.text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Parse the whole line, ignoring surrounding whitespace.
+    pub fn parse<T>(&self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        parse_part(self, self.text)
+    }
+
+    /// Every integer in the line, in order, ignoring any other text. A `-` is a minus sign only when it
+    /// comes straight before a digit and not straight after one, so "x=-3" has -3 but "2-4" has 2 and 4.
+    pub fn ints<T>(&self) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let bytes = self.text.as_bytes();
+        let mut ints = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let signed = bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                && (i == 0 || !bytes[i - 1].is_ascii_digit());
+
+            if signed || bytes[i].is_ascii_digit() {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                ints.push(parse_part(self, &self.text[start..i])?);
+            } else {
+                i += 1;
+            }
+        }
+
+        Ok(ints)
+    }
+
+    /// Split the line into pieces of `width` characters.
+    pub fn columns(&self, width: usize) -> Vec<&'a str> {
+        columns(self.text, width)
+    }
+
+    /// The `width` characters starting at character `start`.
+    pub fn column(&self, start: usize, width: usize) -> Result<&'a str, ParseError> {
+        let boundaries: Vec<usize> = self
+            .text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(iter::once(self.text.len()))
+            .collect();
+
+        match (boundaries.get(start), boundaries.get(start + width)) {
+            (Some(from), Some(to)) => Ok(&self.text[*from..*to]),
+            _ => Err(self.error(format!(
+                "no column of width {} at character {}",
+                width, start
+            ))),
+        }
+    }
+
+    /// Match the line against a template such as "move {} from {} to {}", parsing the text at each `{}` into
+    /// the matching type of the tuple `T`. Surrounding whitespace is ignored, both of the line and of each
+    /// capture.
+    pub fn scan<T: Captures>(&self, template: &str) -> Result<T, ParseError> {
+        let captures = match_template(self.text, template)
+            .ok_or_else(|| self.error(format!("expected \"{}\"", template)))?;
+
+        if captures.len() != T::COUNT {
+            return Err(self.error(format!(
+                "\"{}\" has {} captures, but {} were asked for",
+                template,
+                captures.len(),
+                T::COUNT
+            )));
+        }
+
+        T::from_captures(&captures).map_err(|(i, message)| {
+            self.error(format!(
+                "capture {} of \"{}\": {}",
+                i + 1,
+                template,
+                message
+            ))
+        })
+    }
+
+    /// Split the line at the first `separator` and parse both sides, ignoring surrounding whitespace.
+    pub fn split_once_as<A, B>(&self, separator: &str) -> Result<(A, B), ParseError>
+    where
+        A: FromStr,
+        A::Err: Display,
+        B: FromStr,
+        B::Err: Display,
+    {
+        let (first, second) = self
+            .text
+            .split_once(separator)
+            .ok_or_else(|| self.error(format!("no '{}' to split on", separator)))?;
+
+        Ok((parse_part(self, first)?, parse_part(self, second)?))
+    }
+}
+
+/// Types that can be built from the captures of a `Line::scan` template: tuples of up to five types that
+/// can be parsed from a string.
+pub trait Captures: Sized {
+    const COUNT: usize;
+
+    /// Parse each capture. On failure, gives the index of the capture that couldn't be parsed and why.
+    fn from_captures(captures: &[&str]) -> Result<Self, (usize, String)>;
+}
+
+macro_rules! impl_captures {
+    ($count:expr; $($t:ident $i:tt),*) => {
+        impl<$($t),*> Captures for ($($t,)*)
+        where
+            $($t: FromStr, $t::Err: Display,)*
+        {
+            const COUNT: usize = $count;
+
+            fn from_captures(captures: &[&str]) -> Result<Self, (usize, String)> {
+                Ok(($(parse_trimmed::<$t>(captures[$i]).map_err(|e| ($i, e))?,)*))
+            }
+        }
+    };
+}
+
+impl_captures!(1; A 0);
+impl_captures!(2; A 0, B 1);
+impl_captures!(3; A 0, B 1, C 2);
+impl_captures!(4; A 0, B 1, C 2, D 3);
+impl_captures!(5; A 0, B 1, C 2, D 3, E 4);
+
+/// Match `text` against `template`, returning the text of each `{}`. Leading and trailing whitespace of
+/// both is ignored. A capture ends at the first place the template text after it appears.
+fn match_template<'t>(text: &'t str, template: &str) -> Option<Vec<&'t str>> {
+    let mut literals = template.trim().split("{}");
+    let first = literals.next().unwrap_or_default();
+    let mut rest = text.trim().strip_prefix(first)?;
+    let mut captures = Vec::new();
+
+    for literal in literals {
+        if literal.is_empty() {
+            captures.push(rest);
+            rest = "";
+        } else {
+            let end = rest.find(literal)?;
+            captures.push(&rest[..end]);
+            rest = &rest[end + literal.len()..];
+        }
+    }
+
+    rest.is_empty().then_some(captures)
+}
+
+fn parse_part<T>(line: &Line, part: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    parse_trimmed(part).map_err(|message| line.error(message))
+}
+
+fn parse_trimmed<T>(part: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    part.trim()
+        .parse()
+        .map_err(|e| format!("cannot parse '{}': {}", part.trim(), e))
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{} ", file)?;
+        }
+        write!(
+            f,
+            "line {}: {} in \"{}\"",
+            self.line, self.message, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for std::io::Error {
+    fn from(error: ParseError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+    }
 }
 
 //
@@ -14,16 +326,137 @@ pub fn chunk_string(string: &str, chunk_size: usize) -> Vec<String> {
 mod tests {
     use super::*;
 
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
     #[test]
-    fn test_chunk_str() {
-        let s = "abcdefghi";
+    fn test_columns() {
+        assert_eq!(vec!["[A] ", "    ", "[C]"], columns("[A]     [C]", 4));
+        assert_eq!(vec!["ab", "c"], columns("abc", 2));
+        assert!(columns("", 2).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "column width must be at least 1")]
+    fn test_columns_of_no_width() {
+        columns("abc", 0);
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = lines("\n1\n2\n\n\n3\n  \n4\n5\n\n");
+        let sections = sections(numbered_lines(&input));
+
+        assert_eq!(3, sections.len());
+        assert_eq!(Some(2), sections[0].first_line());
+        assert_eq!(
+            vec!["1", "2"],
+            sections[0].numbered().map(|l| l.text).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Ok(Line {
+                number: 9,
+                text: "5"
+            }),
+            sections[2].line(1)
+        );
+        assert_eq!(9, sections[2].line(2).unwrap_err().line);
+        assert_eq!(
+            vec![8, 9],
+            sections[2].numbered().map(|l| l.number).collect::<Vec<_>>()
+        );
+
+        let empty = Section { lines: Vec::new() };
+        assert_eq!(None, empty.first_line());
+        assert_eq!("section is empty", empty.line(0).unwrap_err().message);
+    }
+
+    #[test]
+    fn test_ints() {
+        let line = Line {
+            number: 3,
+            text: "Sensor at x=-2, y=15: beacon 2-4",
+        };
+        assert_eq!(Ok(vec![-2, 15, 2, 4]), line.ints::<i32>());
+        assert_eq!(
+            Ok(Vec::<u8>::new()),
+            Line {
+                number: 1,
+                text: "none - here"
+            }
+            .ints()
+        );
+
+        let error = line.ints::<u8>().unwrap_err();
+        assert_eq!(3, error.line);
+        assert!(error.message.contains("'-2'"));
+    }
+
+    #[test]
+    fn test_column() {
+        let line = Line {
+            number: 1,
+            text: "[A]     [C]",
+        };
+        assert_eq!(Ok("[C]"), line.column(8, 3));
+        assert_eq!(Ok("   "), line.column(4, 3));
+        assert!(line.column(9, 3).is_err());
+        assert!(line.column(20, 1).is_err());
+    }
+
+    #[test]
+    fn test_split_once_as() {
+        let line = Line {
+            number: 7,
+            text: "R 12",
+        };
+        assert_eq!(Ok(('R', 12_u8)), line.split_once_as(" "));
+
+        let error = line.split_once_as::<char, u8>(",").unwrap_err();
+        assert_eq!("line 7: no ',' to split on in \"R 12\"", error.to_string());
+        assert_eq!(
+            "input.txt line 7: no ',' to split on in \"R 12\"",
+            error.in_file("input.txt").to_string()
+        );
+
+        let error = line.split_once_as::<u8, u8>(" ").unwrap_err();
+        assert_eq!(7, error.line);
+        assert!(error.message.starts_with("cannot parse 'R'"));
+    }
+
+    #[test]
+    fn test_scan() {
+        let line = Line {
+            number: 4,
+            text: "  move 12 from 2 to 1 ",
+        };
+        assert_eq!(
+            Ok((12_u8, 2_usize, 1_usize)),
+            line.scan("move {} from {} to {}")
+        );
+        assert_eq!(
+            Ok((String::from("move"), 2_u8)),
+            line.scan("{} 12 from {} to 1")
+        );
+        assert_eq!(Ok(("2 to 1".to_string(),)), line.scan("move 12 from{}"));
+
+        let error = line
+            .scan::<(u8, u8, u8)>("move {} to {} from {}")
+            .unwrap_err();
+        assert_eq!(
+            "line 4: expected \"move {} to {} from {}\" in \"  move 12 from 2 to 1 \"",
+            error.to_string()
+        );
 
-        let res = chunk_string(s, 3);
-        let expected_values: Vec<String> = vec!["abc".to_string(), "def".into(), "ghi".into()];
-        assert_eq!(expected_values, res);
+        let error = line
+            .scan::<(u8, bool, u8)>("move {} from {} to {}")
+            .unwrap_err();
+        assert!(error
+            .message
+            .starts_with("capture 2 of \"move {} from {} to {}\": cannot parse '2'"));
 
-        let res = chunk_string(s, 4);
-        let expected_values: Vec<String> = vec!["abcd".to_string(), "efgh".into(), "i".into()];
-        assert_eq!(expected_values, res);
+        assert!(line.scan::<(u8, u8)>("move {} from {} to {}").is_err());
+        assert!(line.scan::<(u8,)>("move {} from 2").is_err());
     }
 }